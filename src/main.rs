@@ -1,81 +1,232 @@
-use advent_of_code::template::commands::{all, download, read, scaffold, solve, time};
-use args::{parse, AppArguments};
-
-#[cfg(feature = "today")]
-use advent_of_code::template::Day;
 #[cfg(feature = "today")]
-use std::process;
+use advent_of_code::template::commands::today;
+#[cfg(feature = "tui")]
+use advent_of_code::template::commands::tui;
+use advent_of_code::template::commands::{
+    all, download, read, report, scaffold, solve, stars, time,
+};
+use args::{parse, AppArguments};
 
 mod args {
-    use advent_of_code::template::Day;
-    use std::process;
+    use advent_of_code::template::{
+        commands::scaffold::DEFAULT_TEMPLATE,
+        export::Export,
+        runner::{parse_duration, BenchConfig},
+        Day, Year,
+    };
+    use std::{process, time::Duration};
+
+    /// Pause between requests when downloading several days.
+    const DEFAULT_DOWNLOAD_DELAY: Duration = Duration::from_secs(2);
 
     pub enum AppArguments {
         Download {
-            day: Day,
+            day: Option<Day>,
+            all: bool,
+            missing: bool,
+            force: bool,
+            delay: Duration,
         },
         Read {
             day: Day,
+            refresh: bool,
         },
         Scaffold {
             day: Day,
             download: bool,
             overwrite: bool,
+            dry_run: bool,
+            template: String,
+            from: Option<Day>,
+            answer_type: Option<String>,
         },
         Solve {
             day: Day,
             release: bool,
             dhat: bool,
+            alloc_stats: bool,
             submit: Option<u8>,
+            time: bool,
+            bench_config: BenchConfig,
         },
         All {
             release: bool,
+            alloc_stats: bool,
         },
         Time {
             all: bool,
             day: Option<Day>,
             store: bool,
+            history: bool,
+            export: Option<Export>,
+            check: bool,
+            memory: bool,
+            alloc_stats: bool,
+            bench_config: BenchConfig,
+        },
+        Stars,
+        Report {
+            output: Option<String>,
         },
         #[cfg(feature = "today")]
-        Today,
+        Today {
+            wait: bool,
+        },
+        #[cfg(feature = "tui")]
+        Tui,
     }
 
-    pub fn parse() -> Result<AppArguments, Box<dyn std::error::Error>> {
+    /// Parse the sampling options, validating them like the solution bins do,
+    /// so that invalid values are reported once rather than for every day.
+    fn parse_bench_config(
+        args: &mut pico_args::Arguments,
+    ) -> Result<BenchConfig, Box<dyn std::error::Error>> {
+        let mut flags: Vec<String> = vec![];
+        for flag in ["--samples", "--duration", "--min-samples", "--threads"] {
+            if let Some(value) = args.opt_value_from_str::<_, String>(flag)? {
+                flags.extend([flag.into(), value]);
+            }
+        }
+        Ok(BenchConfig::from_args(&flags)?)
+    }
+
+    /// Parse a day that is part of the calendar of the selected year.
+    fn parse_day(
+        args: &mut pico_args::Arguments,
+        year: Option<Year>,
+    ) -> Result<Day, Box<dyn std::error::Error>> {
+        let day: String = args.free_from_str()?;
+        Ok(Day::parse_in(&day, year)?)
+    }
+
+    fn parse_opt_day(
+        args: &mut pico_args::Arguments,
+        year: Option<Year>,
+    ) -> Result<Option<Day>, Box<dyn std::error::Error>> {
+        let day: Option<String> = args.opt_free_from_str()?;
+        Ok(day.map(|day| Day::parse_in(&day, year)).transpose()?)
+    }
+
+    /// Parse an option with a day value, e.g. `--from 3`.
+    fn parse_opt_value_day(
+        args: &mut pico_args::Arguments,
+        key: &'static str,
+        year: Option<Year>,
+    ) -> Result<Option<Day>, Box<dyn std::error::Error>> {
+        let day: Option<String> = args.opt_value_from_str(key)?;
+        Ok(day.map(|day| Day::parse_in(&day, year)).transpose()?)
+    }
+
+    /// Parse the command and the `--year` option that applies to all commands.
+    pub fn parse() -> Result<(AppArguments, Option<Year>), Box<dyn std::error::Error>> {
         let mut args = pico_args::Arguments::from_env();
 
-        let app_args = match args.subcommand()?.as_deref() {
+        let subcommand = args.subcommand()?;
+        let year = args.opt_value_from_str("--year")?;
+
+        let app_args = match subcommand.as_deref() {
             Some("all") => AppArguments::All {
                 release: args.contains("--release"),
+                alloc_stats: args.contains("--alloc-stats"),
             },
             Some("time") => {
                 let all = args.contains("--all");
                 let store = args.contains("--store");
+                let history = args.contains("--history");
+                let check = args.contains("--check");
+                let export = args
+                    .opt_value_from_str("--export")?
+                    .map(|format| -> Result<Export, pico_args::Error> {
+                        Ok(Export {
+                            format,
+                            output: args.opt_value_from_str("--output")?,
+                        })
+                    })
+                    .transpose()?;
+                let memory = args.contains("--memory");
+                let alloc_stats = args.contains("--alloc-stats");
+                let bench_config = parse_bench_config(&mut args)?;
 
                 AppArguments::Time {
                     all,
-                    day: args.opt_free_from_str()?,
+                    day: parse_opt_day(&mut args, year)?,
                     store,
+                    history,
+                    export,
+                    check,
+                    memory,
+                    alloc_stats,
+                    bench_config,
+                }
+            }
+            Some("download") => {
+                let all = args.contains("--all");
+                let missing = args.contains("--missing");
+                let force = args.contains("--force");
+                let delay = args.opt_value_from_fn("--delay", parse_duration)?;
+
+                // a single day is always downloaded, so these options only apply to bulk downloads.
+                if !all && !missing && (force || delay.is_some()) {
+                    return Err(
+                        "`--force` and `--delay` can only be used with `--all` or `--missing`."
+                            .into(),
+                    );
+                }
+
+                AppArguments::Download {
+                    day: if all || missing {
+                        None
+                    } else {
+                        Some(parse_day(&mut args, year)?)
+                    },
+                    all,
+                    missing,
+                    force,
+                    delay: delay.unwrap_or(DEFAULT_DOWNLOAD_DELAY),
                 }
             }
-            Some("download") => AppArguments::Download {
-                day: args.free_from_str()?,
-            },
             Some("read") => AppArguments::Read {
-                day: args.free_from_str()?,
+                day: parse_day(&mut args, year)?,
+                refresh: args.contains("--refresh"),
             },
             Some("scaffold") => AppArguments::Scaffold {
-                day: args.free_from_str()?,
+                day: parse_day(&mut args, year)?,
                 download: args.contains("--download"),
                 overwrite: args.contains("--overwrite"),
+                dry_run: args.contains("--dry-run"),
+                template: args
+                    .opt_value_from_str("--template")?
+                    .unwrap_or_else(|| DEFAULT_TEMPLATE.into()),
+                from: parse_opt_value_day(&mut args, "--from", year)?,
+                answer_type: args.opt_value_from_str("--answer-type")?,
             },
-            Some("solve") => AppArguments::Solve {
-                day: args.free_from_str()?,
-                release: args.contains("--release"),
-                submit: args.opt_value_from_str("--submit")?,
-                dhat: args.contains("--dhat"),
+            Some("solve") => {
+                let dhat = args.contains("--dhat");
+                let alloc_stats = args.contains("--alloc-stats");
+                if dhat && alloc_stats {
+                    return Err("`--dhat` and `--alloc-stats` can't be combined, as both install a global allocator.".into());
+                }
+
+                AppArguments::Solve {
+                    day: parse_day(&mut args, year)?,
+                    release: args.contains("--release"),
+                    submit: args.opt_value_from_str("--submit")?,
+                    dhat,
+                    alloc_stats,
+                    time: args.contains("--time"),
+                    bench_config: parse_bench_config(&mut args)?,
+                }
+            }
+            Some("stars") => AppArguments::Stars,
+            Some("report") => AppArguments::Report {
+                output: args.opt_value_from_str("--output")?,
             },
             #[cfg(feature = "today")]
-            Some("today") => AppArguments::Today,
+            Some("today") => AppArguments::Today {
+                wait: args.contains("--wait"),
+            },
+            #[cfg(feature = "tui")]
+            Some("tui") => AppArguments::Tui,
             Some(x) => {
                 eprintln!("Unknown command: {x}");
                 process::exit(1);
@@ -91,7 +242,7 @@ mod args {
             eprintln!("Warning: unknown argument(s): {remaining:?}.");
         }
 
-        Ok(app_args)
+        Ok((app_args, year))
     }
 }
 
@@ -101,44 +252,90 @@ fn main() {
             eprintln!("Error: {err}");
             std::process::exit(1);
         }
-        Ok(args) => match args {
-            AppArguments::All { release } => all::handle(release),
-            AppArguments::Time { day, all, store } => time::handle(day, all, store),
-            AppArguments::Download { day } => download::handle(day),
-            AppArguments::Read { day } => read::handle(day),
+        Ok((args, year)) => match args {
+            AppArguments::All {
+                release,
+                alloc_stats,
+            } => all::handle(year, release, alloc_stats),
+            AppArguments::Time {
+                day,
+                all,
+                store,
+                history,
+                export,
+                check,
+                memory,
+                alloc_stats,
+                bench_config,
+            } => time::handle(
+                year,
+                day,
+                all,
+                store,
+                history,
+                export.as_ref(),
+                check,
+                memory,
+                alloc_stats,
+                &bench_config,
+            ),
+            AppArguments::Download {
+                day,
+                all,
+                missing,
+                force,
+                delay,
+            } => match day {
+                Some(day) => download::handle(year, day),
+                None => download::handle_bulk(year, missing && !all, force, delay),
+            },
+            AppArguments::Read { day, refresh } => read::handle(year, day, refresh),
             AppArguments::Scaffold {
                 day,
                 download,
                 overwrite,
+                dry_run,
+                template,
+                from,
+                answer_type,
             } => {
-                scaffold::handle(day, overwrite);
-                if download {
-                    download::handle(day);
+                scaffold::handle(
+                    year,
+                    day,
+                    overwrite,
+                    dry_run,
+                    &template,
+                    from,
+                    answer_type.as_deref(),
+                );
+                if download && !dry_run {
+                    download::handle(year, day);
                 }
             }
             AppArguments::Solve {
                 day,
                 release,
                 dhat,
+                alloc_stats,
                 submit,
-            } => solve::handle(day, release, dhat, submit),
+                time,
+                bench_config,
+            } => solve::handle(
+                year,
+                day,
+                release,
+                dhat,
+                alloc_stats,
+                submit,
+                time,
+                &bench_config,
+            ),
+            AppArguments::Stars => stars::handle(year),
+            AppArguments::Report { output } => report::handle(year, output),
             #[cfg(feature = "today")]
-            AppArguments::Today => {
-                match Day::today() {
-                    Some(day) => {
-                        scaffold::handle(day, false);
-                        download::handle(day);
-                        read::handle(day)
-                    }
-                    None => {
-                        eprintln!(
-                            "`today` command can only be run between the 1st and \
-                            the 25th of december. Please use `scaffold` with a specific day."
-                        );
-                        process::exit(1)
-                    }
-                };
-            }
+            AppArguments::Today { wait } => today::handle(year, wait),
+            #[cfg(feature = "tui")]
+            AppArguments::Tui => tui::handle(year),
         },
     };
 }
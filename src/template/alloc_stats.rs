@@ -0,0 +1,193 @@
+/// A lightweight global allocator wrapper that counts allocations.
+/// It is installed by `solution!` when the `alloc-stats` feature is enabled.
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    collections::HashMap,
+    fmt::Display,
+    sync::atomic::{AtomicU64, Ordering},
+};
+use tinyjson::JsonValue;
+
+#[cfg(all(feature = "alloc-stats", feature = "dhat-heap"))]
+compile_error!("the `alloc-stats` and `dhat-heap` features can't be combined, as both install a global allocator.");
+
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+static ALLOCATED_BYTES: AtomicU64 = AtomicU64::new(0);
+static CURRENT_BYTES: AtomicU64 = AtomicU64::new(0);
+static PEAK_BYTES: AtomicU64 = AtomicU64::new(0);
+
+/// Allocation counts for a single run of a solution part.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AllocStats {
+    /// Number of allocations, including reallocations.
+    pub allocations: u64,
+    /// Total number of bytes allocated.
+    pub bytes: u64,
+    /// Highest number of bytes live at the same time, relative to the start of the run.
+    pub peak_bytes: u64,
+}
+
+/// Wraps the system allocator and counts every allocation made through it.
+pub struct CountingAlloc;
+
+fn record_alloc(size: u64) {
+    ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+    ALLOCATED_BYTES.fetch_add(size, Ordering::Relaxed);
+    let current = CURRENT_BYTES.fetch_add(size, Ordering::Relaxed) + size;
+    PEAK_BYTES.fetch_max(current, Ordering::Relaxed);
+}
+
+fn record_dealloc(size: u64) {
+    CURRENT_BYTES.fetch_sub(size, Ordering::Relaxed);
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            record_alloc(layout.size() as u64);
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            record_alloc(layout.size() as u64);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        record_dealloc(layout.size() as u64);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            record_dealloc(layout.size() as u64);
+            record_alloc(new_size as u64);
+        }
+        new_ptr
+    }
+}
+
+/// Run `func` and count the allocations it makes.
+/// Counts are only meaningful if [`CountingAlloc`] is the global allocator.
+pub fn measure<T>(func: impl FnOnce() -> T) -> (T, AllocStats) {
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let bytes = ALLOCATED_BYTES.load(Ordering::Relaxed);
+    let baseline = CURRENT_BYTES.load(Ordering::Relaxed);
+    PEAK_BYTES.store(baseline, Ordering::Relaxed);
+
+    let result = func();
+
+    let stats = AllocStats {
+        allocations: ALLOCATIONS.load(Ordering::Relaxed) - allocations,
+        bytes: ALLOCATED_BYTES.load(Ordering::Relaxed) - bytes,
+        peak_bytes: PEAK_BYTES.load(Ordering::Relaxed).saturating_sub(baseline),
+    };
+
+    (result, stats)
+}
+
+/* -------------------------------------------------------------------------- */
+
+impl Display for AllocStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "[alloc: {} allocs, {} B total, {} B peak]",
+            self.allocations, self.bytes, self.peak_bytes
+        )
+    }
+}
+
+impl AllocStats {
+    /// Parse the stats from a line of solution output. Inverse of the [`Display`] impl.
+    pub fn parse(line: &str) -> Option<Self> {
+        let (_, stats) = line.split_once("[alloc: ")?;
+        let (stats, _) = stats.split_once(']')?;
+
+        let mut values = stats.split(", ").map(|s| s.split(' ').next()?.parse().ok());
+
+        Some(Self {
+            allocations: values.next()??,
+            bytes: values.next()??,
+            peak_bytes: values.next()??,
+        })
+    }
+}
+
+impl From<&AllocStats> for JsonValue {
+    #[allow(clippy::cast_precision_loss)]
+    fn from(value: &AllocStats) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+        map.insert(
+            "allocations".into(),
+            JsonValue::Number(value.allocations as f64),
+        );
+        map.insert("bytes".into(), JsonValue::Number(value.bytes as f64));
+        map.insert(
+            "peak_bytes".into(),
+            JsonValue::Number(value.peak_bytes as f64),
+        );
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for AllocStats {
+    type Error = String;
+
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected alloc stats to be a JSON object.")?;
+
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let get_number = |key: &str| {
+            json.get(key)
+                .and_then(|v| v.get::<f64>())
+                .map(|x| *x as u64)
+                .ok_or(format!("Expected alloc stats.{key} to be a number."))
+        };
+
+        Ok(AllocStats {
+            allocations: get_number("allocations")?,
+            bytes: get_number("bytes")?,
+            peak_bytes: get_number("peak_bytes")?,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::AllocStats;
+    use tinyjson::JsonValue;
+
+    #[test]
+    fn parses_displayed_stats() {
+        let stats = AllocStats {
+            allocations: 12,
+            bytes: 3456,
+            peak_bytes: 1234,
+        };
+        let line = format!("Part 1: 42 (1.0ms @ 10 samples) {stats}");
+        assert_eq!(AllocStats::parse(&line), Some(stats));
+        assert_eq!(AllocStats::parse("Part 1: 42 (1.0ms @ 10 samples)"), None);
+    }
+
+    #[test]
+    fn roundtrips_json() {
+        let stats = AllocStats {
+            allocations: 1,
+            bytes: 2,
+            peak_bytes: 3,
+        };
+        let json = JsonValue::from(&stats);
+        assert_eq!(AllocStats::try_from(&json), Ok(stats));
+    }
+}
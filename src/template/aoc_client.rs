@@ -0,0 +1,246 @@
+/// Native client for the Advent of Code website.
+/// Authenticates with the same session cookie that aoc-cli uses.
+use std::{env, fmt::Display, fs, path::PathBuf, time::Duration};
+
+use crate::template::{backend::Submission, puzzle_html, Day, Year};
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+const USER_AGENT: &str = "github.com/fspoettel/advent-of-code-rust";
+
+/// Public link to the puzzle page of a day, e.g. for the readme.
+#[must_use]
+pub fn puzzle_url(year: Year, day: Day) -> String {
+    format!("{DEFAULT_BASE_URL}/{year}/day/{}", day.into_inner())
+}
+
+#[derive(Debug)]
+pub enum AocClientError {
+    SessionNotFound,
+    YearNotSet,
+    Request(String),
+    BadStatus(u16, String),
+}
+
+impl Display for AocClientError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AocClientError::SessionNotFound => write!(
+                f,
+                "no session cookie found. Set `ADVENT_OF_CODE_SESSION` or create the file \"~/.adventofcode.session\"."
+            ),
+            AocClientError::YearNotSet => write!(f, "`AOC_YEAR` is not set."),
+            AocClientError::Request(e) => write!(f, "request failed: {e}"),
+            AocClientError::BadStatus(status, _) => {
+                write!(f, "server responded with status {status}.")
+            }
+        }
+    }
+}
+
+impl From<ureq::Error> for AocClientError {
+    fn from(e: ureq::Error) -> Self {
+        match e {
+            ureq::Error::Status(status, response) => {
+                AocClientError::BadStatus(status, response.into_string().unwrap_or_default())
+            }
+            ureq::Error::Transport(e) => AocClientError::Request(e.to_string()),
+        }
+    }
+}
+
+pub struct AocClient {
+    agent: ureq::Agent,
+    base_url: String,
+    session: String,
+    year: u16,
+}
+
+impl AocClient {
+    pub fn new(base_url: &str, session: &str, year: u16) -> Self {
+        let agent = ureq::AgentBuilder::new()
+            .user_agent(USER_AGENT)
+            .timeout(Duration::from_secs(30))
+            .build();
+
+        AocClient {
+            agent,
+            base_url: base_url.trim_end_matches('/').into(),
+            session: session.trim().into(),
+            year,
+        }
+    }
+
+    /// Create a client from the environment. The year defaults to `AOC_YEAR`.
+    /// The base url can be overridden with `AOC_BASE_URL`, e.g. to point it to a local server.
+    pub fn from_env(year: Option<Year>) -> Result<Self, AocClientError> {
+        let base_url = env::var("AOC_BASE_URL").unwrap_or_else(|_| DEFAULT_BASE_URL.into());
+        let session = read_session().ok_or(AocClientError::SessionNotFound)?;
+        let year = year
+            .or_else(Year::from_env)
+            .ok_or(AocClientError::YearNotSet)?;
+        Ok(Self::new(&base_url, &session, year.into_inner()))
+    }
+
+    fn day_url(&self, day: Day) -> String {
+        format!("{}/{}/day/{}", self.base_url, self.year, day.into_inner())
+    }
+
+    fn get(&self, url: &str) -> Result<String, AocClientError> {
+        let response = self
+            .agent
+            .get(url)
+            .set("Cookie", &format!("session={}", self.session))
+            .call()?;
+
+        response
+            .into_string()
+            .map_err(|e| AocClientError::Request(e.to_string()))
+    }
+
+    /// Download the puzzle input for a day.
+    pub fn download_input(&self, day: Day) -> Result<String, AocClientError> {
+        self.get(&format!("{}/input", self.day_url(day)))
+    }
+
+    /// Fetch the puzzle description for a day as markdown.
+    pub fn fetch_puzzle(&self, day: Day) -> Result<String, AocClientError> {
+        let html = self.get(&self.day_url(day))?;
+        Ok(puzzle_html::extract_description(&html))
+    }
+
+    /// Submit an answer for one part of a day.
+    pub fn submit(&self, day: Day, part: u8, answer: &str) -> Result<Submission, AocClientError> {
+        let html = self
+            .agent
+            .post(&format!("{}/answer", self.day_url(day)))
+            .set("Cookie", &format!("session={}", self.session))
+            .send_form(&[("level", &part.to_string()), ("answer", answer)])?
+            .into_string()
+            .map_err(|e| AocClientError::Request(e.to_string()))?;
+
+        let message = puzzle_html::extract_article_text(&html)
+            .ok_or_else(|| AocClientError::Request("unexpected response.".into()))?;
+
+        Ok(Submission::from_message(message))
+    }
+}
+
+/// Read the session cookie from `ADVENT_OF_CODE_SESSION` or `~/.adventofcode.session`.
+fn read_session() -> Option<String> {
+    if let Ok(session) = env::var("ADVENT_OF_CODE_SESSION") {
+        return Some(session);
+    }
+
+    let home = env::var_os("HOME").or_else(|| env::var_os("USERPROFILE"))?;
+    let session = fs::read_to_string(PathBuf::from(home).join(".adventofcode.session")).ok()?;
+
+    if session.trim().is_empty() {
+        None
+    } else {
+        Some(session)
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::AocClient;
+    use crate::day;
+    use crate::template::backend::Verdict;
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        sync::mpsc,
+        thread,
+    };
+
+    /// Serve one canned response per request on a local port and report the received requests.
+    fn serve(responses: Vec<&'static str>) -> (String, mpsc::Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let (tx, rx) = mpsc::channel();
+
+        thread::spawn(move || {
+            for body in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+                let mut request = String::new();
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if let Some(len) = line.to_lowercase().strip_prefix("content-length:") {
+                        content_length = len.trim().parse().unwrap();
+                    }
+                    if line == "\r\n" {
+                        break;
+                    }
+                    request.push_str(&line);
+                }
+                let mut body_buf = vec![0; content_length];
+                reader.read_exact(&mut body_buf).unwrap();
+                request.push_str(&String::from_utf8(body_buf).unwrap());
+                tx.send(request).unwrap();
+
+                write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                )
+                .unwrap();
+            }
+        });
+
+        (url, rx)
+    }
+
+    #[test]
+    fn downloads_input() {
+        let (url, requests) = serve(vec!["1 2\n3 4\n"]);
+        let client = AocClient::new(&url, "abc\n", 2024);
+
+        assert_eq!(client.download_input(day!(3)).unwrap(), "1 2\n3 4\n");
+
+        let request = requests.recv().unwrap();
+        assert_eq!(request.starts_with("GET /2024/day/3/input "), true);
+        assert_eq!(request.contains("session=abc\r\n"), true);
+    }
+
+    #[test]
+    fn fetches_puzzle() {
+        let (url, _requests) = serve(vec![
+            "<main><article><h2>--- Day 3: Mull It Over ---</h2><p>Hi.</p></article></main>",
+        ]);
+        let client = AocClient::new(&url, "abc", 2024);
+
+        assert_eq!(
+            client.fetch_puzzle(day!(3)).unwrap(),
+            "## --- Day 3: Mull It Over ---\n\nHi.\n"
+        );
+    }
+
+    #[test]
+    fn submits_answer() {
+        let (url, requests) = serve(vec![
+            "<main><article><p>That's the right answer! You are one gold star closer.</p></article></main>",
+            "<main><article><p>That's not the right answer; your answer is too high.</p></article></main>",
+        ]);
+        let client = AocClient::new(&url, "abc", 2024);
+
+        let submission = client.submit(day!(3), 1, "42").unwrap();
+        assert_eq!(submission.verdict, Verdict::Correct);
+        let request = requests.recv().unwrap();
+        assert_eq!(request.starts_with("POST /2024/day/3/answer "), true);
+        assert_eq!(request.ends_with("level=1&answer=42"), true);
+
+        let submission = client.submit(day!(3), 2, "43").unwrap();
+        assert_eq!(submission.verdict, Verdict::Incorrect);
+        assert_eq!(
+            submission.message,
+            "That's not the right answer; your answer is too high."
+        );
+    }
+}
@@ -0,0 +1,305 @@
+/// Abstracts over the ways of talking to Advent of Code.
+/// Besides the native client and aoc-cli, a filesystem-backed fake allows running the full flow offline.
+use std::{
+    env,
+    fmt::Display,
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::PathBuf,
+};
+
+use crate::template::{
+    aoc_cli::{self, AocCommandError},
+    aoc_client::{AocClient, AocClientError},
+    paths, Day, Year,
+};
+
+/// The verdict for a submitted answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    Incorrect,
+    TooSoon,
+    WrongLevel,
+    Unknown,
+}
+
+/// The response to a submitted answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Submission {
+    pub verdict: Verdict,
+    pub message: String,
+}
+
+impl Submission {
+    /// Derive the verdict from the message that Advent of Code responds with.
+    pub fn from_message(message: String) -> Self {
+        let verdict = if message.contains("That's the right answer") {
+            Verdict::Correct
+        } else if message.contains("That's not the right answer") {
+            Verdict::Incorrect
+        } else if message.contains("You gave an answer too recently") {
+            Verdict::TooSoon
+        } else if message.contains("You don't seem to be solving the right level") {
+            Verdict::WrongLevel
+        } else {
+            Verdict::Unknown
+        };
+
+        Submission { verdict, message }
+    }
+}
+
+#[derive(Debug)]
+pub enum BackendError {
+    AocCli(AocCommandError),
+    Client(AocClientError),
+    IO(io::Error),
+    UnknownBackend(String),
+}
+
+impl Display for BackendError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BackendError::AocCli(e) => write!(f, "{e}"),
+            BackendError::Client(e) => write!(f, "{e}"),
+            BackendError::IO(e) => write!(f, "{e}"),
+            BackendError::UnknownBackend(name) => write!(
+                f,
+                "unknown backend `{name}`, expected one of `native`, `aoc-cli` or `fake`."
+            ),
+        }
+    }
+}
+
+impl From<AocCommandError> for BackendError {
+    fn from(e: AocCommandError) -> Self {
+        BackendError::AocCli(e)
+    }
+}
+
+impl From<AocClientError> for BackendError {
+    fn from(e: AocClientError) -> Self {
+        BackendError::Client(e)
+    }
+}
+
+impl From<io::Error> for BackendError {
+    fn from(e: io::Error) -> Self {
+        BackendError::IO(e)
+    }
+}
+
+pub trait PuzzleBackend {
+    /// Download the input and puzzle description for a day to the `data` directory.
+    fn download(&self, day: Day) -> Result<(), BackendError>;
+
+    /// Fetch the puzzle description for a day as markdown.
+    fn read(&self, day: Day) -> Result<String, BackendError>;
+
+    /// Submit an answer for one part of a day.
+    fn submit(&self, day: Day, part: u8, answer: &str) -> Result<Submission, BackendError>;
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Talks to the Advent of Code website with the native client.
+pub struct NativeBackend {
+    pub client: AocClient,
+    /// The year that determines where downloaded files are written to.
+    pub year: Option<Year>,
+}
+
+impl PuzzleBackend for NativeBackend {
+    fn download(&self, day: Day) -> Result<(), BackendError> {
+        let input = self.client.download_input(day)?;
+        let puzzle = self.client.fetch_puzzle(day)?;
+        fs::write(paths::input_path(self.year, day), input)?;
+        fs::write(paths::puzzle_path(self.year, day), puzzle)?;
+        Ok(())
+    }
+
+    fn read(&self, day: Day) -> Result<String, BackendError> {
+        Ok(self.client.fetch_puzzle(day)?)
+    }
+
+    fn submit(&self, day: Day, part: u8, answer: &str) -> Result<Submission, BackendError> {
+        Ok(self.client.submit(day, part, answer)?)
+    }
+}
+
+/// Delegates to the external `aoc` command.
+pub struct AocCliBackend {
+    pub year: Option<Year>,
+}
+
+impl PuzzleBackend for AocCliBackend {
+    fn download(&self, day: Day) -> Result<(), BackendError> {
+        aoc_cli::download(self.year, day)?;
+        Ok(())
+    }
+
+    /// aoc-cli prints the description rendered for the terminal, so the markdown it wrote to the puzzle file is returned instead.
+    fn read(&self, day: Day) -> Result<String, BackendError> {
+        aoc_cli::read(self.year, day)?;
+        Ok(fs::read_to_string(paths::puzzle_path(self.year, day))?)
+    }
+
+    fn submit(&self, day: Day, part: u8, answer: &str) -> Result<Submission, BackendError> {
+        let output = aoc_cli::submit(self.year, day, part, answer)?;
+        let message = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        Ok(Submission::from_message(message))
+    }
+}
+
+/// Serves canned inputs, puzzles and answers from a directory, without any network access.
+///
+/// The directory contains `inputs/NN.txt`, `puzzles/NN.md` and `answers/NN-P.txt`.
+/// Every submission is appended to `submissions.log`. Once part one was solved,
+/// `puzzles/NN-2.md` is served as the description instead, if it exists.
+pub struct FakeBackend {
+    pub root: PathBuf,
+    pub year: Option<Year>,
+}
+
+impl FakeBackend {
+    fn read_file(&self, folder: &str, name: &str) -> Result<String, BackendError> {
+        Ok(fs::read_to_string(self.root.join(folder).join(name))?)
+    }
+
+    fn is_solved(&self, day: Day, part: u8) -> bool {
+        let Ok(answer) = self.read_file("answers", &format!("{day}-{part}.txt")) else {
+            return false;
+        };
+        let entry = format!("{day}-{part}: {}", answer.trim());
+        fs::read_to_string(self.root.join("submissions.log"))
+            .is_ok_and(|log| log.lines().any(|line| line == entry))
+    }
+}
+
+impl PuzzleBackend for FakeBackend {
+    fn download(&self, day: Day) -> Result<(), BackendError> {
+        let input = self.read_file("inputs", &format!("{day}.txt"))?;
+        let puzzle = self.read_file("puzzles", &format!("{day}.md"))?;
+        fs::write(paths::input_path(self.year, day), input)?;
+        fs::write(paths::puzzle_path(self.year, day), puzzle)?;
+        Ok(())
+    }
+
+    fn read(&self, day: Day) -> Result<String, BackendError> {
+        if self.is_solved(day, 1) {
+            if let Ok(puzzle) = self.read_file("puzzles", &format!("{day}-2.md")) {
+                return Ok(puzzle);
+            }
+        }
+        self.read_file("puzzles", &format!("{day}.md"))
+    }
+
+    fn submit(&self, day: Day, part: u8, answer: &str) -> Result<Submission, BackendError> {
+        let mut log = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.root.join("submissions.log"))?;
+        writeln!(log, "{day}-{part}: {answer}")?;
+
+        let message = match self.read_file("answers", &format!("{day}-{part}.txt")) {
+            Ok(expected) if expected.trim() == answer.trim() => {
+                "That's the right answer! You are one gold star closer."
+            }
+            Ok(_) => "That's not the right answer.",
+            Err(_) => "You don't seem to be solving the right level.",
+        };
+
+        Ok(Submission::from_message(message.into()))
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+/// Select a backend based on the `AOC_BACKEND` environment variable.
+/// Without a year, the year configured in `AOC_YEAR` is used.
+///
+///  - `native` (default): the native client. Falls back to aoc-cli if no session is configured.
+///  - `aoc-cli`: the external `aoc` command.
+///  - `fake`: canned data from the directory in `AOC_FAKE_DIR` (default: `data/fake`).
+pub fn get_backend(year: Option<Year>) -> Result<Box<dyn PuzzleBackend>, BackendError> {
+    let backend = env::var("AOC_BACKEND").unwrap_or_else(|_| "native".into());
+
+    match backend.as_str() {
+        "native" => match AocClient::from_env(year) {
+            Ok(client) => Ok(Box::new(NativeBackend { client, year })),
+            Err(_) if aoc_cli::check().is_ok() => Ok(Box::new(AocCliBackend { year })),
+            Err(e) => Err(e.into()),
+        },
+        "aoc-cli" => {
+            aoc_cli::check()?;
+            Ok(Box::new(AocCliBackend { year }))
+        }
+        "fake" => Ok(Box::new(FakeBackend {
+            root: env::var_os("AOC_FAKE_DIR").map_or_else(|| "data/fake".into(), PathBuf::from),
+            year,
+        })),
+        name => Err(BackendError::UnknownBackend(name.into())),
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{FakeBackend, PuzzleBackend, Submission, Verdict};
+    use crate::day;
+    use std::{env, fs, process};
+
+    #[test]
+    fn derives_verdicts() {
+        let verdict = |s: &str| Submission::from_message(s.into()).verdict;
+        assert_eq!(verdict("That's the right answer!"), Verdict::Correct);
+        assert_eq!(verdict("That's not the right answer."), Verdict::Incorrect);
+        assert_eq!(
+            verdict("You gave an answer too recently; you have 30s left to wait."),
+            Verdict::TooSoon
+        );
+        assert_eq!(
+            verdict("You don't seem to be solving the right level."),
+            Verdict::WrongLevel
+        );
+        assert_eq!(verdict("???"), Verdict::Unknown);
+    }
+
+    #[test]
+    fn fake_backend_serves_canned_data() {
+        let root = env::temp_dir().join(format!("aoc-fake-backend-{}", process::id()));
+        fs::create_dir_all(root.join("puzzles")).unwrap();
+        fs::create_dir_all(root.join("answers")).unwrap();
+        fs::write(root.join("puzzles/07.md"), "## --- Day 7 ---\n").unwrap();
+        fs::write(root.join("puzzles/07-2.md"), "## --- Part Two ---\n").unwrap();
+        fs::write(root.join("answers/07-1.txt"), "42\n").unwrap();
+
+        let backend = FakeBackend {
+            root: root.clone(),
+            year: None,
+        };
+
+        assert_eq!(backend.read(day!(7)).unwrap(), "## --- Day 7 ---\n");
+        assert_eq!(
+            backend.submit(day!(7), 1, "42").unwrap().verdict,
+            Verdict::Correct
+        );
+        assert_eq!(backend.read(day!(7)).unwrap(), "## --- Part Two ---\n");
+        assert_eq!(
+            backend.submit(day!(7), 1, "41").unwrap().verdict,
+            Verdict::Incorrect
+        );
+        assert_eq!(
+            backend.submit(day!(7), 2, "1").unwrap().verdict,
+            Verdict::WrongLevel
+        );
+        assert_eq!(
+            fs::read_to_string(root.join("submissions.log")).unwrap(),
+            "07-1: 42\n07-1: 41\n07-2: 1\n"
+        );
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
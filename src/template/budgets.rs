@@ -0,0 +1,212 @@
+/// Time budgets that benchmarks are checked against with `cargo time --check`.
+/// Budgets are declared in `data/budgets.json`, e.g. `{ "total": "1s", "day": "100ms", "days": { "15": "500ms" } }`.
+use std::{collections::HashMap, fmt::Display, fs, io::ErrorKind, str::FromStr, time::Duration};
+use tinyjson::JsonValue;
+
+use crate::template::runner::parse_duration;
+use crate::template::timings::Timings;
+use crate::template::{paths, Day, Year};
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Budgets {
+    /// Budget for the sum of all days, in nanoseconds.
+    pub total: Option<f64>,
+    /// Budget for days without a budget of their own, in nanoseconds.
+    pub day: Option<f64>,
+    /// Budgets of single days, in nanoseconds.
+    pub days: HashMap<Day, f64>,
+}
+
+/// A day, or the total if `day` is `None`, that took longer than its budget.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Overrun {
+    pub day: Option<Day>,
+    pub nanos: f64,
+    pub budget: f64,
+}
+
+/// Convert nanoseconds to a duration, which displays like the timings of the runner, e.g. `74.13µs`.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+pub fn to_duration(nanos: f64) -> Duration {
+    Duration::from_nanos(nanos as u64)
+}
+
+impl Display for Overrun {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = self
+            .day
+            .map_or_else(|| "Total".into(), |day| format!("Day {}", day.into_inner()));
+        write!(
+            f,
+            "{label}: {:?} (budget: {:?})",
+            to_duration(self.nanos),
+            to_duration(self.budget)
+        )
+    }
+}
+
+impl Budgets {
+    /// Read the budgets of a year. Returns `None` if no budgets are configured.
+    pub fn read_from_file(year: Option<Year>) -> Result<Option<Self>, String> {
+        let path = paths::budgets_path(year);
+        match fs::read_to_string(&path) {
+            Ok(contents) => Budgets::try_from(contents)
+                .map(Some)
+                .map_err(|e| format!("{path}: {e}")),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(format!("{path}: {e}")),
+        }
+    }
+
+    pub fn budget_for(&self, day: Day) -> Option<f64> {
+        self.days.get(&day).copied().or(self.day)
+    }
+
+    /// Compare timings against the budgets, days first and the total last.
+    pub fn check(&self, timings: &Timings) -> Vec<Overrun> {
+        let mut overruns: Vec<Overrun> = timings
+            .data
+            .iter()
+            .filter_map(|timing| {
+                let budget = self.budget_for(timing.day)?;
+                (timing.total_nanos > budget).then_some(Overrun {
+                    day: Some(timing.day),
+                    nanos: timing.total_nanos,
+                    budget,
+                })
+            })
+            .collect();
+
+        let total = timings.total_millis() * 1_000_000_f64;
+        if let Some(budget) = self.total.filter(|budget| total > *budget) {
+            overruns.push(Overrun {
+                day: None,
+                nanos: total,
+                budget,
+            });
+        }
+
+        overruns
+    }
+}
+
+fn parse_budget(value: &JsonValue, key: &str) -> Result<f64, String> {
+    let s = value.get::<String>().ok_or(format!(
+        "expected `{key}` to be a duration, e.g. \"100ms\"."
+    ))?;
+    let duration = parse_duration(s).map_err(|e| format!("`{key}`: {e}"))?;
+    Ok(duration.as_secs_f64() * 1_000_000_000_f64)
+}
+
+impl TryFrom<String> for Budgets {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let json = JsonValue::from_str(&value).or(Err("not valid JSON file."))?;
+
+        let json = json
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("expected JSON document to be an object.")?;
+
+        let mut days = HashMap::new();
+        if let Some(value) = json.get("days") {
+            let map = value
+                .get::<HashMap<String, JsonValue>>()
+                .ok_or("expected `days` to be an object.")?;
+
+            for (key, value) in map {
+                let day = Day::from_str(key).map_err(|e| format!("`days.{key}`: {e}"))?;
+                days.insert(day, parse_budget(value, &format!("days.{key}"))?);
+            }
+        }
+
+        Ok(Budgets {
+            total: json
+                .get("total")
+                .map(|v| parse_budget(v, "total"))
+                .transpose()?,
+            day: json
+                .get("day")
+                .map(|v| parse_budget(v, "day"))
+                .transpose()?,
+            days,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{Budgets, Overrun};
+    use crate::{
+        day,
+        template::timings::{
+            fixtures::{mock_timing, mock_timings_of},
+            Timings,
+        },
+    };
+
+    fn get_mock_timings() -> Timings {
+        mock_timings_of(vec![
+            mock_timing(day!(1), &[Some("2ms"), Some("1ms")]),
+            mock_timing(day!(2), &[Some("40ms"), Some("80ms")]),
+        ])
+    }
+
+    #[test]
+    fn parses_budgets() {
+        let budgets = Budgets::try_from(
+            r#"{ "total": "1s", "day": "100ms", "days": { "2": "500ms" } }"#.to_string(),
+        )
+        .unwrap();
+
+        assert_eq!(budgets.total, Some(1e9));
+        assert_eq!(budgets.budget_for(day!(1)), Some(1e8));
+        assert_eq!(budgets.budget_for(day!(2)), Some(5e8));
+        assert_eq!(Budgets::try_from("{}".to_string()), Ok(Budgets::default()));
+    }
+
+    #[test]
+    fn rejects_invalid_budgets() {
+        assert_eq!(
+            Budgets::try_from(r#"{ "day": "soon" }"#.to_string()).is_err(),
+            true
+        );
+        assert_eq!(
+            Budgets::try_from(r#"{ "days": { "26": "1s" } }"#.to_string()).is_err(),
+            true
+        );
+    }
+
+    #[test]
+    fn finds_overruns() {
+        let budgets = Budgets {
+            total: Some(1e8),
+            day: Some(1e7),
+            ..Budgets::default()
+        };
+
+        assert_eq!(
+            budgets.check(&get_mock_timings()),
+            vec![
+                Overrun {
+                    day: Some(day!(2)),
+                    nanos: 1.2e8,
+                    budget: 1e7
+                },
+                Overrun {
+                    day: None,
+                    nanos: 1.23e8,
+                    budget: 1e8
+                }
+            ]
+        );
+
+        assert_eq!(
+            budgets.check(&get_mock_timings())[0].to_string(),
+            "Day 2: 120ms (budget: 10ms)"
+        );
+        assert_eq!(Budgets::default().check(&get_mock_timings()), vec![]);
+    }
+}
@@ -0,0 +1,320 @@
+/// Module that renders the stored timings as an SVG bar chart, without external tools.
+/// Times are plotted on a log scale, since solutions range from nanoseconds to seconds.
+use crate::template::{history::Snapshot, html::escape, timings::Timings};
+
+/// Bar colors by the time a part takes, with the legend label of every tier.
+const TIERS: [(f64, &str, &str); 4] = [
+    (1e6, "#2ea043", "< 1ms"),
+    (1e7, "#d4a72c", "< 10ms"),
+    (1e8, "#e16f24", "< 100ms"),
+    (f64::INFINITY, "#cf222e", "≥ 100ms"),
+];
+
+/// Colors of the labels, the gridlines, the markers of the day totals and the trend line.
+const TEXT_COLOR: &str = "#57606a";
+const GRID_COLOR: &str = "#d0d7de";
+const TOTAL_COLOR: &str = "#24292f";
+const TREND_COLOR: &str = "#0969da";
+
+const LEFT: f64 = 56.0;
+const RIGHT: f64 = 16.0;
+const TOP: f64 = 36.0;
+const PLOT_HEIGHT: f64 = 220.0;
+const TREND_HEIGHT: f64 = 100.0;
+const BAR_WIDTH: f64 = 10.0;
+const BAR_GAP: f64 = 2.0;
+const DAY_GAP: f64 = 12.0;
+const MIN_WIDTH: f64 = 560.0;
+
+fn tier_color(nanos: f64) -> &'static str {
+    TIERS
+        .iter()
+        .find(|(limit, _, _)| nanos < *limit)
+        .map_or(TIERS[TIERS.len() - 1].1, |(_, color, _)| color)
+}
+
+/// Label of a power of ten nanoseconds, e.g. `100µs` for `5`.
+fn format_decade(exp: i32) -> String {
+    let unit = match exp {
+        ..=2 => "ns",
+        3..=5 => "µs",
+        6..=8 => "ms",
+        _ => "s",
+    };
+    let exp = if exp < 9 { exp.max(0) % 3 } else { exp - 9 };
+    format!("{}{unit}", 10_u64.pow(exp as u32))
+}
+
+/// Maps nanoseconds onto a vertical axis, spanning whole decades.
+struct LogScale {
+    min: i32,
+    max: i32,
+}
+
+impl LogScale {
+    fn new(values: impl Iterator<Item = f64>) -> Self {
+        let (min, max) = values
+            .filter(|nanos| *nanos > 0.0)
+            .map(f64::log10)
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), x| {
+                (min.min(x), max.max(x))
+            });
+
+        if min > max {
+            return LogScale { min: 0, max: 3 };
+        }
+
+        let min = (min.floor() as i32).max(0);
+        let max = (max.ceil() as i32).max(min + 1);
+        LogScale { min, max }
+    }
+
+    /// The y coordinate of a time within a plot area.
+    fn y(&self, nanos: f64, top: f64, height: f64) -> f64 {
+        let x = nanos
+            .max(1.0)
+            .log10()
+            .clamp(self.min as f64, self.max as f64);
+        top + height * (1.0 - (x - self.min as f64) / (self.max - self.min) as f64)
+    }
+
+    /// Horizontal gridlines with a label for every decade.
+    fn grid(&self, top: f64, height: f64, right: f64) -> Vec<String> {
+        (self.min..=self.max)
+            .map(|exp| {
+                let y = self.y(10_f64.powi(exp), top, height);
+                format!(
+                    r#"<line x1="{LEFT}" y1="{y:.1}" x2="{right:.1}" y2="{y:.1}" stroke="{GRID_COLOR}"/><text x="{:.1}" y="{:.1}" text-anchor="end">{}</text>"#,
+                    LEFT - 6.0,
+                    y + 4.0,
+                    format_decade(exp)
+                )
+            })
+            .collect()
+    }
+}
+
+/// Render a chart of the time of every part, grouped by day, with a marker for the total time of every day.
+/// If at least two snapshots of the history are passed, a trend of the total time is drawn below.
+pub fn render(timings: &Timings, history: &[Snapshot]) -> String {
+    let part_count = timings.part_count().max(1);
+    let group_width = part_count as f64 * (BAR_WIDTH + BAR_GAP) - BAR_GAP + DAY_GAP;
+    let width = (LEFT + timings.data.len() as f64 * group_width + RIGHT).max(MIN_WIDTH);
+    let right = width - RIGHT;
+    let bottom = TOP + PLOT_HEIGHT;
+
+    let show_trend = history.len() >= 2;
+    let legend_y = bottom + 40.0;
+    let trend_top = legend_y + 48.0;
+    let height = if show_trend {
+        trend_top + TREND_HEIGHT + 32.0
+    } else {
+        legend_y + 16.0
+    };
+
+    let scale = LogScale::new(timings.data.iter().flat_map(|timing| {
+        timing
+            .parts
+            .iter()
+            .filter_map(|part| part.nanos())
+            .chain([timing.total_nanos])
+    }));
+
+    let mut lines: Vec<String> = vec![
+        format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width:.0}" height="{height:.0}" viewBox="0 0 {width:.0} {height:.0}" font-family="sans-serif" font-size="11" fill="{TEXT_COLOR}">"#
+        ),
+        format!(r##"<rect width="{width:.0}" height="{height:.0}" fill="#ffffff"/>"##),
+        format!(
+            r#"<text x="{LEFT}" y="20" font-size="14" font-weight="bold">Time per part and day (log scale)</text>"#
+        ),
+    ];
+
+    lines.extend(scale.grid(TOP, PLOT_HEIGHT, right));
+
+    for (i, timing) in timings.data.iter().enumerate() {
+        let x = LEFT + i as f64 * group_width + DAY_GAP / 2.0;
+
+        for (n, part) in timing.parts.iter().enumerate() {
+            let Some(nanos) = part.nanos() else {
+                continue;
+            };
+            let bar_x = x + n as f64 * (BAR_WIDTH + BAR_GAP);
+            let y = scale.y(nanos, TOP, PLOT_HEIGHT);
+            lines.push(format!(
+                r#"<rect x="{bar_x:.1}" y="{y:.1}" width="{BAR_WIDTH}" height="{:.1}" fill="{}"><title>Day {} · Part {}: {}</title></rect>"#,
+                bottom - y,
+                tier_color(nanos),
+                timing.day.into_inner(),
+                n + 1,
+                escape(part.time.as_deref().unwrap_or_default())
+            ));
+        }
+
+        if timing.total_nanos > 0.0 {
+            let y = scale.y(timing.total_nanos, TOP, PLOT_HEIGHT);
+            lines.push(format!(
+                r#"<line x1="{:.1}" y1="{y:.1}" x2="{:.1}" y2="{y:.1}" stroke="{TOTAL_COLOR}" stroke-width="2"><title>Day {} · Total: {:.2}ms</title></line>"#,
+                x - BAR_GAP,
+                x + group_width - DAY_GAP + BAR_GAP,
+                timing.day.into_inner(),
+                timing.total_nanos / 1_000_000_f64
+            ));
+        }
+
+        lines.push(format!(
+            r#"<text x="{:.1}" y="{:.1}" text-anchor="middle">{}</text>"#,
+            x + (group_width - DAY_GAP) / 2.0,
+            bottom + 16.0,
+            timing.day.into_inner()
+        ));
+    }
+
+    lines.push(format!(
+        r#"<text x="{:.1}" y="{:.1}" text-anchor="middle">Day</text>"#,
+        (LEFT + right) / 2.0,
+        bottom + 30.0
+    ));
+
+    for (i, (_, color, label)) in TIERS.iter().enumerate() {
+        let x = LEFT + i as f64 * 96.0;
+        lines.push(format!(
+            r#"<rect x="{x}" y="{:.1}" width="10" height="10" fill="{color}"/><text x="{}" y="{legend_y:.1}">{}</text>"#,
+            legend_y - 9.0,
+            x + 14.0,
+            escape(label)
+        ));
+    }
+
+    let x = LEFT + TIERS.len() as f64 * 96.0;
+    lines.push(format!(
+        r#"<line x1="{x}" y1="{:.1}" x2="{}" y2="{:.1}" stroke="{TOTAL_COLOR}" stroke-width="2"/><text x="{}" y="{legend_y:.1}">Day total</text>"#,
+        legend_y - 4.0,
+        x + 10.0,
+        legend_y - 4.0,
+        x + 14.0
+    ));
+
+    if show_trend {
+        lines.extend(render_trend(history, trend_top, right));
+    }
+
+    lines.push("</svg>".into());
+    lines.join("\n") + "\n"
+}
+
+/// A line of the total time of every snapshot, oldest first.
+fn render_trend(history: &[Snapshot], top: f64, right: f64) -> Vec<String> {
+    let scale = LogScale::new(history.iter().map(|snapshot| snapshot.total_nanos));
+    let step = (right - LEFT) / (history.len() - 1) as f64;
+
+    let points: Vec<(f64, f64, &Snapshot)> = history
+        .iter()
+        .enumerate()
+        .map(|(i, snapshot)| {
+            let y = scale.y(snapshot.total_nanos, top, TREND_HEIGHT);
+            (LEFT + i as f64 * step, y, snapshot)
+        })
+        .collect();
+
+    let mut lines = vec![format!(
+        r#"<text x="{LEFT}" y="{:.1}" font-size="14" font-weight="bold">Total time per run</text>"#,
+        top - 16.0
+    )];
+    lines.extend(scale.grid(top, TREND_HEIGHT, right));
+
+    let polyline: Vec<String> = points
+        .iter()
+        .map(|(x, y, _)| format!("{x:.1},{y:.1}"))
+        .collect();
+    lines.push(format!(
+        r#"<polyline points="{}" fill="none" stroke="{TREND_COLOR}" stroke-width="2"/>"#,
+        polyline.join(" ")
+    ));
+
+    for (x, y, snapshot) in &points {
+        lines.push(format!(
+            r#"<circle cx="{x:.1}" cy="{y:.1}" r="3" fill="{TREND_COLOR}"><title>{}: {:.2}ms</title></circle>"#,
+            escape(&snapshot.label),
+            snapshot.total_nanos / 1_000_000_f64
+        ));
+    }
+
+    if let (Some((_, _, first)), Some((_, _, last))) = (points.first(), points.last()) {
+        lines.push(format!(
+            r#"<text x="{LEFT}" y="{:.1}">{}</text><text x="{right:.1}" y="{:.1}" text-anchor="end">{}</text>"#,
+            top + TREND_HEIGHT + 16.0,
+            escape(&first.label),
+            top + TREND_HEIGHT + 16.0,
+            escape(&last.label)
+        ));
+    }
+
+    lines
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{format_decade, render, tier_color, LogScale};
+    use crate::template::{history::Snapshot, timings::fixtures::mock_timings};
+
+    #[test]
+    fn picks_tier_colors() {
+        assert_eq!(tier_color(74_130.0), "#2ea043");
+        assert_eq!(tier_color(2.5e6), "#d4a72c");
+        assert_eq!(tier_color(5e7), "#e16f24");
+        assert_eq!(tier_color(1.2e9), "#cf222e");
+    }
+
+    #[test]
+    fn labels_decades() {
+        assert_eq!(format_decade(0), "1ns");
+        assert_eq!(format_decade(4), "10µs");
+        assert_eq!(format_decade(8), "100ms");
+        assert_eq!(format_decade(10), "10s");
+    }
+
+    #[test]
+    fn scales_logarithmically() {
+        let scale = LogScale::new([74_130.0, 1.2e9].into_iter());
+        assert_eq!((scale.min, scale.max), (4, 10));
+        assert_eq!(scale.y(1e4, 0.0, 60.0), 60.0);
+        assert_eq!(scale.y(1e7, 0.0, 60.0), 30.0);
+        assert_eq!(scale.y(1e10, 0.0, 60.0), 0.0);
+    }
+
+    #[test]
+    fn renders_bars_per_part_and_day() {
+        let svg = render(&mock_timings(), &[]);
+        assert_eq!(svg.starts_with("<svg "), true);
+        assert_eq!(svg.ends_with("</svg>\n"), true);
+        assert_eq!(svg.matches(" · Part ").count(), 3);
+        assert_eq!(svg.contains("<title>Day 1 · Part 2: 2.5ms</title>"), true);
+        assert_eq!(svg.matches(" · Total: ").count(), 2);
+        assert_eq!(
+            svg.contains("<title>Day 2 · Total: 1200.00ms</title>"),
+            true
+        );
+        assert_eq!(svg.contains("<polyline"), false);
+    }
+
+    #[test]
+    fn renders_trend() {
+        let history = vec![
+            Snapshot {
+                label: "a1b2c3d".into(),
+                total_nanos: 2e9,
+            },
+            Snapshot {
+                label: "<dirty>".into(),
+                total_nanos: 1.2e9,
+            },
+        ];
+        let svg = render(&mock_timings(), &history);
+        assert_eq!(svg.contains("<polyline"), true);
+        assert_eq!(svg.contains("<title>a1b2c3d: 2000.00ms</title>"), true);
+        assert_eq!(svg.contains("&lt;dirty&gt;"), true);
+    }
+}
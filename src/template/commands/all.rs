@@ -1,5 +1,12 @@
-use crate::template::{all_days, run_multi::run_multi};
+use crate::template::{all_days_in, run_multi::run_multi, runner::BenchConfig, Year};
 
-pub fn handle(is_release: bool) {
-    run_multi(&all_days().collect(), is_release, false);
+pub fn handle(year: Option<Year>, is_release: bool, alloc_stats: bool) {
+    run_multi(
+        year,
+        &all_days_in(year).collect(),
+        is_release,
+        false,
+        alloc_stats,
+        &BenchConfig::default(),
+    );
 }
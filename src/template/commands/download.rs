@@ -1,14 +1,126 @@
-use crate::template::{aoc_cli, Day};
-use std::process;
+use std::{fs, process, thread, time::Duration};
 
-pub fn handle(day: Day) {
-    if aoc_cli::check().is_err() {
-        eprintln!("command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.");
-        process::exit(1);
+use crate::template::{
+    all_days_in,
+    backend::{get_backend, PuzzleBackend},
+    paths, Day, Year,
+};
+
+fn backend_or_exit(year: Option<Year>) -> Box<dyn PuzzleBackend> {
+    let data_dir = paths::data_dir(year);
+    for folder in ["inputs", "puzzles"] {
+        if let Err(e) = fs::create_dir_all(format!("{data_dir}/{folder}")) {
+            eprintln!("failed to create data directory: {e}");
+            process::exit(1);
+        }
+    }
+
+    match get_backend(year) {
+        Ok(backend) => backend,
+        Err(e) => {
+            eprintln!("failed to set up backend: {e}");
+            process::exit(1);
+        }
+    }
+}
+
+pub fn handle(year: Option<Year>, day: Day) {
+    handle_with_retries(year, day, 0, Duration::ZERO);
+}
+
+/// Download a day, retrying a few times if it fails, e.g. because the input is not ready yet right after the unlock.
+pub fn handle_with_retries(year: Option<Year>, day: Day, retries: u32, delay: Duration) {
+    let backend = backend_or_exit(year);
+
+    let mut attempt = 0;
+    while let Err(e) = backend.download(day) {
+        if attempt == retries {
+            eprintln!("failed to download puzzle: {e}");
+            process::exit(1);
+        }
+        attempt += 1;
+        eprintln!("failed to download puzzle: {e}, retrying in {delay:?}...");
+        thread::sleep(delay);
     }
 
-    if let Err(e) = aoc_cli::download(day) {
-        eprintln!("failed to call aoc-cli: {e}");
+    println!("---");
+    println!(
+        "🎄 Successfully wrote input to \"{}\".",
+        paths::input_path(year, day)
+    );
+    println!(
+        "🎄 Successfully wrote puzzle to \"{}\".",
+        paths::puzzle_path(year, day)
+    );
+}
+
+enum Outcome {
+    Downloaded,
+    Skipped,
+    Failed(String),
+}
+
+/// A day counts as downloaded once it has a non-empty input and a puzzle description.
+fn is_downloaded(year: Option<Year>, day: Day) -> bool {
+    fs::metadata(paths::input_path(year, day)).is_ok_and(|m| m.len() > 0)
+        && fs::metadata(paths::puzzle_path(year, day)).is_ok()
+}
+
+/// Download every unlocked day of a year, by default the year configured in `AOC_YEAR`.
+/// Days that already have a non-empty input and a puzzle description are skipped unless `force` is set.
+/// With `missing_only`, these days are not listed in the summary.
+pub fn handle_bulk(year: Option<Year>, missing_only: bool, force: bool, delay: Duration) {
+    let Some(event_year) = year.or_else(Year::from_env) else {
+        eprintln!("`AOC_YEAR` or `--year` needs to be set to download all days.");
         process::exit(1);
     };
+
+    let days: Vec<Day> = all_days_in(Some(event_year))
+        .filter(|day| day.is_unlocked(event_year.into_inner()))
+        .filter(|day| !missing_only || force || !is_downloaded(year, *day))
+        .collect();
+
+    if days.is_empty() {
+        println!("🎄 Nothing to download.");
+        return;
+    }
+
+    let backend = backend_or_exit(year);
+    let mut results = Vec::with_capacity(days.len());
+    let mut need_pause = false;
+
+    for day in days {
+        let outcome = if is_downloaded(year, day) && !force {
+            Outcome::Skipped
+        } else {
+            // don't hammer the servers.
+            if need_pause {
+                thread::sleep(delay);
+            }
+            need_pause = true;
+
+            println!("Downloading day {day}...");
+            match backend.download(day) {
+                Ok(()) => Outcome::Downloaded,
+                Err(e) => Outcome::Failed(e.to_string()),
+            }
+        };
+        results.push((day, outcome));
+    }
+
+    println!("---");
+    for (day, outcome) in &results {
+        match outcome {
+            Outcome::Downloaded => println!("🎄 Day {day}: downloaded."),
+            Outcome::Skipped => println!("⏭️  Day {day}: skipped, already downloaded."),
+            Outcome::Failed(e) => println!("❌ Day {day}: failed: {e}"),
+        }
+    }
+
+    if results
+        .iter()
+        .any(|(_, outcome)| matches!(outcome, Outcome::Failed(_)))
+    {
+        process::exit(1);
+    }
 }
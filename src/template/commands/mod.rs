@@ -1,6 +1,12 @@
 pub mod all;
 pub mod download;
 pub mod read;
+pub mod report;
 pub mod scaffold;
 pub mod solve;
+pub mod stars;
 pub mod time;
+#[cfg(feature = "today")]
+pub mod today;
+#[cfg(feature = "tui")]
+pub mod tui;
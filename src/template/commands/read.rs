@@ -1,15 +1,46 @@
-use std::process;
+use std::{
+    fs,
+    io::{self, IsTerminal},
+    process,
+};
 
-use crate::template::{aoc_cli, Day};
+use crate::template::{backend::get_backend, paths, puzzle_render, Day, Year};
 
-pub fn handle(day: Day) {
-    if aoc_cli::check().is_err() {
-        eprintln!("command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.");
-        process::exit(1);
+/// Fetch the puzzle description and update the local copy.
+fn fetch(year: Option<Year>, day: Day) -> String {
+    let backend = match get_backend(year) {
+        Ok(backend) => backend,
+        Err(e) => {
+            eprintln!("failed to set up backend: {e}");
+            process::exit(1);
+        }
+    };
+
+    match backend.read(day) {
+        Ok(puzzle) => {
+            let _ = fs::write(paths::puzzle_path(year, day), &puzzle);
+            puzzle
+        }
+        Err(e) => {
+            eprintln!("failed to read puzzle: {e}");
+            process::exit(1);
+        }
     }
+}
 
-    if let Err(e) = aoc_cli::read(day) {
-        eprintln!("failed to call aoc-cli: {e}");
-        process::exit(1);
+/// Render the puzzle description for a day.
+/// The local copy in `data/puzzles` is used unless it is missing or `refresh` is set.
+pub fn handle(year: Option<Year>, day: Day, refresh: bool) {
+    let cached = fs::read_to_string(paths::puzzle_path(year, day))
+        .ok()
+        .filter(|puzzle| !puzzle.trim().is_empty());
+
+    let puzzle = match cached {
+        Some(puzzle) if !refresh => puzzle,
+        _ => fetch(year, day),
     };
+
+    // piped output is left plain, so it doesn't contain escape codes.
+    let color = io::stdout().is_terminal();
+    puzzle_render::print_paged(&puzzle_render::render(&puzzle, color));
 }
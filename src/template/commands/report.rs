@@ -0,0 +1,15 @@
+use std::process;
+
+use crate::template::{paths, report, Year};
+
+pub fn handle(year: Option<Year>, output: Option<String>) {
+    let dir = output.unwrap_or_else(|| paths::report_dir(year));
+
+    match report::write(year, &dir) {
+        Ok(pages) => println!("📄 Wrote the report to `{dir}/index.html` ({pages} day pages)."),
+        Err(e) => {
+            eprintln!("Failed to write the report: {e}");
+            process::exit(1);
+        }
+    }
+}
@@ -1,79 +1,819 @@
-use std::{
-    fs::{File, OpenOptions},
-    io::Write,
-    process,
-};
+use std::{fs, io, path::Path, process};
 
-use crate::template::Day;
+use crate::template::{paths, puzzle_info, Day, Year};
 
-const MODULE_TEMPLATE: &str =
-    include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/template.txt"));
+/// Templates in this directory take precedence over the built-in ones, e.g. `src/templates/grid.txt`.
+const TEMPLATE_DIR: &str = "src/templates";
 
-fn safe_create_file(path: &str, overwrite: bool) -> Result<File, std::io::Error> {
-    let mut file = OpenOptions::new();
-    if overwrite {
-        file.create(true);
+const BUILTIN_TEMPLATES: &[(&str, &str)] = &[
+    (
+        DEFAULT_TEMPLATE,
+        include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/src/templates/default.txt"
+        )),
+    ),
+    (
+        "grid",
+        include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/src/templates/grid.txt"
+        )),
+    ),
+];
+
+pub const DEFAULT_TEMPLATE: &str = "default";
+
+const DEFAULT_ANSWER_TYPE: &str = "u32";
+
+/// Values for the placeholders of a module template.
+struct TemplateValues {
+    day: Day,
+    year: Option<Year>,
+    title: Option<String>,
+    answer_type: String,
+    example_answers: [Option<String>; 2],
+    helpers: String,
+}
+
+fn load_template(name: &str) -> Result<String, String> {
+    let path = format!("{TEMPLATE_DIR}/{name}.txt");
+
+    match fs::read_to_string(&path) {
+        Ok(template) => Ok(template),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => BUILTIN_TEMPLATES
+            .iter()
+            .find(|(builtin, _)| *builtin == name)
+            .map(|(_, template)| (*template).to_string())
+            .ok_or_else(|| format!("unknown template \"{name}\", expected a file at \"{path}\"")),
+        Err(e) => Err(format!("could not read \"{path}\": {e}")),
+    }
+}
+
+fn is_numeric(answer: &str) -> bool {
+    answer.parse::<i64>().is_ok() || answer.parse::<u64>().is_ok()
+}
+
+/// Pick the smallest answer type that fits all example answers, so that the generated tests compile.
+fn infer_answer_type(answers: &[Option<String>]) -> &'static str {
+    let answers: Vec<&str> = answers.iter().flatten().map(String::as_str).collect();
+    let fits = |parse: fn(&str) -> bool| answers.iter().all(|answer| parse(answer));
+
+    if fits(|a| a.parse::<u32>().is_ok()) {
+        DEFAULT_ANSWER_TYPE
+    } else if fits(|a| a.parse::<u64>().is_ok()) {
+        "u64"
+    } else if fits(|a| a.parse::<i64>().is_ok()) {
+        "i64"
     } else {
-        file.create_new(true);
+        "String"
     }
-    file.truncate(true).write(true).open(path)
 }
 
-fn create_file(path: &str) -> Result<File, std::io::Error> {
-    OpenOptions::new()
-        .write(true)
-        .create(true)
-        .truncate(true)
-        .open(path)
+/// Format an example answer as the expected value of a test, e.g. `Some(11)` or `None`.
+fn format_answer(answer: Option<&str>, answer_type: &str) -> String {
+    match answer {
+        Some(answer) if is_numeric(answer) && answer_type != "String" => format!("Some({answer})"),
+        Some(answer) => format!("Some(String::from({answer:?}))"),
+        None => "None".into(),
+    }
 }
 
-pub fn handle(day: Day, overwrite: bool) {
-    let input_path = format!("data/inputs/{day}.txt");
-    let example_path = format!("data/examples/{day}.txt");
-    let module_path = format!("src/bin/{day}.rs");
+fn render_template(template: &str, values: &TemplateValues) -> String {
+    let day = values.day.into_inner().to_string();
+    let title = values.title.clone().unwrap_or_else(|| format!("Day {day}"));
+    let year = values.year.map(|year| year.to_string()).unwrap_or_default();
 
-    let mut file = match safe_create_file(&module_path, overwrite) {
-        Ok(file) => file,
-        Err(e) => {
-            eprintln!("Failed to create module file: {e}");
-            process::exit(1);
-        }
+    let helpers = if values.helpers.is_empty() {
+        String::new()
+    } else {
+        format!("{}\n\n", values.helpers)
     };
 
-    match file.write_all(
-        MODULE_TEMPLATE
-            .replace("%DAY_NUMBER%", &day.into_inner().to_string())
-            .as_bytes(),
-    ) {
-        Ok(()) => {
-            println!("Created module file \"{}\"", &module_path);
+    template
+        .replace("%HELPERS%\n\n", &helpers)
+        .replace("%HELPERS%", values.helpers.as_str())
+        .replace("%DAY_NUMBER%", &day)
+        .replace("%YEAR%", &year)
+        .replace("%TITLE%", &title)
+        .replace("%ANSWER_TYPE%", &values.answer_type)
+        .replace(
+            "%EXAMPLE_ANSWER_1%",
+            &format_answer(values.example_answers[0].as_deref(), &values.answer_type),
+        )
+        .replace(
+            "%EXAMPLE_ANSWER_2%",
+            &format_answer(values.example_answers[1].as_deref(), &values.answer_type),
+        )
+}
+
+/// Split a rust module into its top-level items, including their doc comments and attributes.
+/// This relies on items being formatted by rustfmt, i.e. only their first and last line are unindented.
+fn split_items(source: &str) -> Vec<String> {
+    let mut items = vec![];
+    let mut item: Vec<&str> = vec![];
+    let mut is_closed = false;
+
+    for line in source.lines() {
+        if line.trim().is_empty() {
+            if is_closed {
+                items.push(item.join("\n"));
+                item.clear();
+                is_closed = false;
+            } else if !item.is_empty() {
+                item.push(line);
+            }
+            continue;
         }
-        Err(e) => {
-            eprintln!("Failed to write module contents: {e}");
-            process::exit(1);
+
+        let is_unindented = !line.starts_with([' ', '\t']);
+        let is_prefix = line.starts_with("//") || line.starts_with('#');
+        is_closed = is_unindented && !is_prefix && (line.ends_with('}') || line.ends_with(';'));
+        item.push(line);
+    }
+
+    if !item.is_empty() {
+        items.push(item.join("\n"));
+    }
+
+    items
+}
+
+/// Extract the helpers of an existing solution: everything but the solution macro, the parts and the tests.
+fn extract_helpers(source: &str) -> String {
+    split_items(source)
+        .into_iter()
+        .filter(|item| {
+            let Some(declaration) = item
+                .lines()
+                .find(|line| !line.starts_with("//") && !line.starts_with('#'))
+            else {
+                return false;
+            };
+
+            let is_part = ["pub fn part_", "fn part_"]
+                .iter()
+                .any(|prefix| declaration.starts_with(prefix));
+
+            !is_part
+                && !declaration.contains("solution!(")
+                && !declaration.starts_with("mod tests")
+                && !item.contains("#[cfg(test)]")
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// A change to a single path. All changes are planned before anything is written.
+enum Change {
+    CreateDir,
+    /// Create a new file or fill an empty one.
+    Create(String),
+    /// Back up a non-empty file and replace it.
+    Replace {
+        contents: String,
+        backup: String,
+    },
+    /// Update a file in place, e.g. add an entry to `Cargo.toml`.
+    Update(String),
+    Skip(&'static str),
+}
+
+struct Step {
+    path: String,
+    label: String,
+    change: Change,
+}
+
+/// Reverts a step that was already applied when a later step fails.
+enum Undo {
+    RemoveDir(String),
+    RemoveFile(String),
+    Restore(String, String),
+}
+
+impl Step {
+    fn describe(&self, dry_run: bool) -> String {
+        let verb = |present: &str, past: &str| if dry_run { present } else { past }.to_string();
+        let Step { path, label, .. } = self;
+
+        match &self.change {
+            Change::CreateDir => format!("{} directory \"{path}\"", verb("Create", "Created")),
+            Change::Create(contents) if contents.is_empty() => {
+                format!("{} empty {label} \"{path}\"", verb("Create", "Created"))
+            }
+            Change::Create(_) => format!("{} {label} \"{path}\"", verb("Create", "Created")),
+            Change::Replace { backup, .. } => format!(
+                "{} {label} \"{path}\", backup in \"{backup}\"",
+                verb("Replace", "Replaced")
+            ),
+            Change::Update(_) => format!("{} \"{path}\" to {label}", verb("Update", "Updated")),
+            Change::Skip(reason) => {
+                format!("{} {label} \"{path}\": {reason}", verb("Skip", "Skipped"))
+            }
+        }
+    }
+
+    fn apply(&self, undo: &mut Vec<Undo>) -> Result<(), io::Error> {
+        let path = &self.path;
+
+        let contents = match &self.change {
+            Change::Skip(_) => return Ok(()),
+            Change::CreateDir => {
+                fs::create_dir(path)?;
+                undo.push(Undo::RemoveDir(path.clone()));
+                return Ok(());
+            }
+            Change::Replace { contents, backup } => {
+                fs::copy(path, backup)?;
+                undo.push(Undo::RemoveFile(backup.clone()));
+                contents
+            }
+            Change::Create(contents) | Change::Update(contents) => contents,
+        };
+
+        let original = match fs::read_to_string(path) {
+            Ok(original) => Undo::Restore(path.clone(), original),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Undo::RemoveFile(path.clone()),
+            Err(e) => return Err(e),
+        };
+
+        // register the undo first, so a write that fails halfway is reverted as well.
+        undo.push(original);
+        write_file(path, contents)
+    }
+}
+
+impl Undo {
+    fn revert(&self) -> Result<(), io::Error> {
+        match self {
+            Undo::RemoveDir(path) => fs::remove_dir(path),
+            Undo::RemoveFile(path) => match fs::remove_file(path) {
+                Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+                result => result,
+            },
+            Undo::Restore(path, contents) => write_file(path, contents),
         }
     }
+}
+
+/// Temporary file that contents are written to before they are moved into place.
+fn temp_path(path: &str) -> String {
+    format!("{path}.{}.tmp", process::id())
+}
+
+/// Write a file through a temporary file, so that an existing file is never left truncated.
+fn write_file(path: &str, contents: &str) -> Result<(), io::Error> {
+    let temp = temp_path(path);
+    fs::write(&temp, contents)
+        .and_then(|()| fs::rename(&temp, path))
+        .inspect_err(|_| {
+            let _ = fs::remove_file(&temp);
+        })
+}
+
+/// Pick a backup path that does not exist yet, e.g. `src/bin/01.rs.bak`.
+fn backup_path(path: &str) -> String {
+    let mut backup = format!("{path}.bak");
+    let mut n = 1;
+    while Path::new(&backup).exists() {
+        backup = format!("{path}.bak.{n}");
+        n += 1;
+    }
+    backup
+}
 
-    match create_file(&input_path) {
-        Ok(_) => {
-            println!("Created empty input file \"{}\"", &input_path);
+fn plan_dir(path: String) -> Option<Step> {
+    (!Path::new(&path).exists()).then(|| Step {
+        path,
+        label: "directory".into(),
+        change: Change::CreateDir,
+    })
+}
+
+/// Plan writing a file. Existing files that are not empty are only replaced if `overwrite` is `Some(true)`.
+/// `None` means the file is never replaced, e.g. for inputs.
+fn plan_file(
+    path: String,
+    label: &str,
+    contents: String,
+    overwrite: Option<bool>,
+) -> Result<Step, io::Error> {
+    let change = match fs::read_to_string(&path) {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Change::Create(contents),
+        Err(e) => return Err(e),
+        Ok(existing) if existing == contents => Change::Skip("already exists"),
+        Ok(existing) if existing.is_empty() => Change::Create(contents),
+        Ok(_) => match overwrite {
+            Some(true) => Change::Replace {
+                contents,
+                backup: backup_path(&path),
+            },
+            Some(false) => Change::Skip("not empty, pass `--overwrite` to replace it"),
+            None => Change::Skip("not empty"),
+        },
+    };
+
+    Ok(Step {
+        path,
+        label: label.into(),
+        change,
+    })
+}
+
+/// Solutions in per-year directories are not discovered by cargo automatically,
+/// so they need a `[[bin]]` entry in `Cargo.toml`.
+fn plan_register_bin(year: Year, day: Day) -> Result<Option<Step>, io::Error> {
+    let name = paths::bin_name(Some(year), day);
+    let manifest = fs::read_to_string("Cargo.toml")?;
+
+    if manifest.contains(&format!("name = \"{name}\"")) {
+        return Ok(None);
+    }
+
+    let entry = format!(
+        "\n[[bin]]\nname = \"{name}\"\npath = \"{}\"\n",
+        paths::bin_path(Some(year), day)
+    );
+
+    Ok(Some(Step {
+        path: "Cargo.toml".into(),
+        label: format!("register bin \"{name}\""),
+        change: Change::Update(manifest + &entry),
+    }))
+}
+
+/// Apply all steps. If one of them fails, the steps that were already applied are reverted.
+fn apply(steps: &[Step]) -> Result<(), (String, io::Error)> {
+    let mut undo = vec![];
+
+    for step in steps {
+        if let Err(e) = step.apply(&mut undo) {
+            for undo in undo.iter().rev() {
+                if let Err(e) = undo.revert() {
+                    eprintln!("Failed to revert a change: {e}");
+                }
+            }
+            return Err((step.describe(true), e));
         }
+        println!("{}", step.describe(false));
+    }
+
+    Ok(())
+}
+
+const LAUNCH_CONFIG_PATH: &str = ".vscode/launch.json";
+
+fn launch_configuration(name: &str, title: &str, command: &str, extra_args: &[&str]) -> String {
+    let mut args = vec![format!("\"{command}\"")];
+    args.extend(extra_args.iter().map(|arg| format!("\"{arg}\"")));
+    args.push(format!("\"--bin={name}\""));
+    args.push("\"--package=advent_of_code\"".into());
+
+    let args = args
+        .iter()
+        .map(|arg| format!("                    {arg}"))
+        .collect::<Vec<_>>()
+        .join(",\n");
+
+    format!(
+        r#"        {{
+            "type": "lldb",
+            "request": "launch",
+            "name": "{title}",
+            "cargo": {{
+                "args": [
+{args}
+                ]
+            }},
+            "args": [],
+            "cwd": "${{workspaceFolder}}"
+        }}"#
+    )
+}
+
+/// Add debug configurations for a bin to the start of the `configurations` list of a launch config.
+/// The file may contain comments, so configurations are inserted as text and other entries are left untouched.
+/// Returns `None` if the configurations for this bin already exist.
+fn add_launch_configurations(launch: &str, year: Option<Year>, day: Day) -> Option<String> {
+    let name = paths::bin_name(year, day);
+    let label = match year {
+        Some(year) => format!("{year} day {day}"),
+        None => format!("day {day}"),
+    };
+
+    let configurations: Vec<String> = [
+        (
+            format!("Debug unit tests for {label}"),
+            "test",
+            &["--no-run"][..],
+        ),
+        (format!("Debug {label}"), "build", &[][..]),
+    ]
+    .into_iter()
+    .filter(|(title, _, _)| !launch.contains(&format!("\"name\": \"{title}\"")))
+    .map(|(title, command, args)| launch_configuration(&name, &title, command, args))
+    .collect();
+
+    if configurations.is_empty() {
+        return None;
+    }
+
+    let key = launch.find("\"configurations\"")?;
+    let start = launch[key..].find('[')? + key + 1;
+    let is_empty = launch[start..].trim_start().starts_with(']');
+
+    let mut entries = format!("\n{}", configurations.join(",\n"));
+    if !is_empty {
+        entries.push(',');
+    } else if launch[start..].starts_with(']') {
+        entries.push('\n');
+    }
+
+    let mut launch = launch.to_string();
+    launch.insert_str(start, &entries);
+    Some(launch)
+}
+
+/// Add debug configurations for the new bin, if the repository contains a VS Code launch config.
+fn plan_launch_configurations(year: Option<Year>, day: Day) -> Result<Option<Step>, io::Error> {
+    let launch = match fs::read_to_string(LAUNCH_CONFIG_PATH) {
+        Ok(launch) => launch,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+
+    Ok(
+        add_launch_configurations(&launch, year, day).map(|launch| Step {
+            path: LAUNCH_CONFIG_PATH.into(),
+            label: "add debug configurations".into(),
+            change: Change::Update(launch),
+        }),
+    )
+}
+
+/// Plan all changes that are needed to scaffold a day.
+fn plan(
+    year: Option<Year>,
+    day: Day,
+    overwrite: bool,
+    module: String,
+) -> Result<Vec<Step>, io::Error> {
+    let mut steps = vec![];
+
+    if let Some(year) = year {
+        let data_dir = paths::data_dir(Some(year));
+        let dirs = [
+            format!("src/bin/{year}"),
+            data_dir.clone(),
+            format!("{data_dir}/inputs"),
+            format!("{data_dir}/examples"),
+            format!("{data_dir}/puzzles"),
+        ];
+        steps.extend(dirs.into_iter().filter_map(plan_dir));
+    }
+
+    steps.push(plan_file(
+        paths::bin_path(year, day),
+        "module file",
+        module,
+        Some(overwrite),
+    )?);
+    steps.push(plan_file(
+        paths::input_path(year, day),
+        "input file",
+        String::new(),
+        None,
+    )?);
+    steps.push(plan_file(
+        paths::example_path(year, day),
+        "example file",
+        String::new(),
+        None,
+    )?);
+
+    if let Some(year) = year {
+        steps.extend(plan_register_bin(year, day)?);
+    }
+    steps.extend(plan_launch_configurations(year, day)?);
+
+    Ok(steps)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn handle(
+    year: Option<Year>,
+    day: Day,
+    overwrite: bool,
+    dry_run: bool,
+    template: &str,
+    from: Option<Day>,
+    answer_type: Option<&str>,
+) {
+    let template = match load_template(template) {
+        Ok(template) => template,
         Err(e) => {
-            eprintln!("Failed to create input file: {e}");
+            eprintln!("Failed to load template: {e}");
             process::exit(1);
         }
-    }
+    };
 
-    match create_file(&example_path) {
-        Ok(_) => {
-            println!("Created empty example file \"{}\"", &example_path);
+    let helpers = match from {
+        Some(from) => {
+            let source_path = paths::bin_path(year, from);
+            match fs::read_to_string(&source_path) {
+                Ok(source) => extract_helpers(&source),
+                Err(e) => {
+                    eprintln!("Failed to read solution \"{source_path}\": {e}");
+                    process::exit(1);
+                }
+            }
         }
+        None => String::new(),
+    };
+
+    // title and example answers are only known if the puzzle was downloaded before.
+    let puzzle = fs::read_to_string(paths::puzzle_path(year, day)).unwrap_or_default();
+    let example_answers = [1, 2].map(|part| puzzle_info::example_answer(&puzzle, part));
+
+    let answer_type = answer_type.map_or_else(
+        || infer_answer_type(&example_answers).to_string(),
+        String::from,
+    );
+
+    let contents = render_template(
+        &template,
+        &TemplateValues {
+            day,
+            year: year.or_else(Year::from_env),
+            title: puzzle_info::title(&puzzle),
+            answer_type,
+            example_answers,
+            helpers,
+        },
+    );
+
+    let steps = match plan(year, day, overwrite, contents) {
+        Ok(steps) => steps,
         Err(e) => {
-            eprintln!("Failed to create example file: {e}");
+            eprintln!("Failed to plan scaffolding: {e}");
             process::exit(1);
         }
+    };
+
+    if dry_run {
+        for step in &steps {
+            println!("{}", step.describe(true));
+        }
+        println!("---");
+        println!("🎄 Dry run, nothing was written.");
+        return;
+    }
+
+    if let Err((step, e)) = apply(&steps) {
+        eprintln!("{step} failed: {e}");
+        eprintln!("Reverted all changes.");
+        process::exit(1);
     }
 
     println!("---");
-    println!("🎄 Type `cargo solve {day}` to run your solution.");
+    match year {
+        Some(year) => println!("🎄 Type `cargo solve {day} --year {year}` to run your solution."),
+        None => println!("🎄 Type `cargo solve {day}` to run your solution."),
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{
+        add_launch_configurations, apply, extract_helpers, infer_answer_type, plan_file,
+        render_template, temp_path, Change, Step, TemplateValues,
+    };
+    use crate::{day, template::Year};
+    use std::{env, fs, path::PathBuf, process};
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("aoc-scaffold-{name}-{}", process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn values() -> TemplateValues {
+        TemplateValues {
+            day: day!(6),
+            year: Year::new(2024),
+            title: Some("Day 6: Guard Gallivant".into()),
+            answer_type: "u64".into(),
+            example_answers: [Some("41".into()), None],
+            helpers: String::new(),
+        }
+    }
+
+    #[test]
+    fn renders_placeholders() {
+        let template = "// %TITLE% (%YEAR%)\nsolution!(%DAY_NUMBER%);\n\nfn a() -> Option<%ANSWER_TYPE%> {}\n\n%HELPERS%\n\nassert_eq!(a, %EXAMPLE_ANSWER_1%);\nassert_eq!(b, %EXAMPLE_ANSWER_2%);\n";
+        assert_eq!(
+            render_template(template, &values()),
+            "// Day 6: Guard Gallivant (2024)\nsolution!(6);\n\nfn a() -> Option<u64> {}\n\nassert_eq!(a, Some(41));\nassert_eq!(b, None);\n"
+        );
+
+        let mut values = values();
+        values.title = None;
+        values.answer_type = "String".into();
+        values.helpers = "fn helper() {}".into();
+        assert_eq!(
+            render_template("// %TITLE%\n%HELPERS%\n\n%EXAMPLE_ANSWER_1%", &values),
+            "// Day 6\nfn helper() {}\n\nSome(String::from(\"41\"))"
+        );
+    }
+
+    #[test]
+    fn infers_answer_type() {
+        let answers = |a: &str, b: &str| [Some(a.to_string()), Some(b.to_string())];
+        assert_eq!(infer_answer_type(&[None, None]), "u32");
+        assert_eq!(infer_answer_type(&answers("41", "4294967295")), "u32");
+        assert_eq!(infer_answer_type(&answers("41", "4294967296")), "u64");
+        assert_eq!(infer_answer_type(&answers("-3", "41")), "i64");
+        assert_eq!(infer_answer_type(&answers("41", "abc")), "String");
+    }
+
+    #[test]
+    fn extracts_helpers() {
+        let source = r#"// Day 1
+advent_of_code::solution!(1);
+
+use std::collections::HashMap;
+
+/// Parse a line.
+fn parse(line: &str) -> u32 {
+    let value = line.parse().unwrap();
+
+    value
+}
+
+pub fn part_one(input: &str) -> Option<u32> {
+    input.lines().map(parse).sum::<u32>().into()
+}
+
+pub fn part_two(input: &str) -> Option<u32> {
+    None
+}
+
+#[derive(Debug)]
+struct Point(u32, u32);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+}
+"#;
+
+        assert_eq!(
+            extract_helpers(source),
+            "use std::collections::HashMap;\n\n/// Parse a line.\nfn parse(line: &str) -> u32 {\n    let value = line.parse().unwrap();\n\n    value\n}\n\n#[derive(Debug)]\nstruct Point(u32, u32);"
+        );
+    }
+
+    const LAUNCH: &str = r#"{
+    // a comment
+    "version": "0.2.0",
+    "configurations": [
+        {
+            "name": "Custom",
+        }
+    ]
+}"#;
+
+    #[test]
+    fn adds_launch_configurations() {
+        let launch = add_launch_configurations(LAUNCH, None, day!(1)).unwrap();
+        assert_eq!(
+            launch.contains("\"name\": \"Debug unit tests for day 01\""),
+            true
+        );
+        assert_eq!(launch.contains("\"name\": \"Debug day 01\""), true);
+        assert_eq!(launch.matches("\"--bin=01\"").count(), 2);
+        assert_eq!(
+            launch.contains("        },\n        {\n            \"name\": \"Custom\","),
+            true
+        );
+        assert_eq!(launch.starts_with("{\n    // a comment\n"), true);
+
+        assert_eq!(add_launch_configurations(&launch, None, day!(1)), None);
+
+        let launch = add_launch_configurations(&launch, Year::new(2023), day!(2)).unwrap();
+        assert_eq!(launch.contains("\"name\": \"Debug 2023 day 02\""), true);
+        assert_eq!(launch.contains("\"--bin=2023-02\""), true);
+    }
+
+    #[test]
+    fn adds_launch_configurations_to_empty_list() {
+        let launch = add_launch_configurations("{ \"configurations\": [] }", None, day!(3));
+        assert_eq!(launch.unwrap().ends_with("}\n] }"), true);
+    }
+
+    #[test]
+    fn plans_file_changes() {
+        let dir = temp_dir("plan");
+        let path = |name: &str| dir.join(name).to_string_lossy().to_string();
+        fs::write(path("empty.txt"), "").unwrap();
+        fs::write(path("full.txt"), "input").unwrap();
+
+        let change = |name: &str, overwrite| {
+            plan_file(path(name), "file", "new".into(), overwrite)
+                .unwrap()
+                .change
+        };
+
+        assert!(matches!(change("missing.txt", None), Change::Create(_)));
+        assert!(matches!(change("empty.txt", None), Change::Create(_)));
+        assert!(matches!(
+            change("full.txt", None),
+            Change::Skip("not empty")
+        ));
+        assert!(matches!(change("full.txt", Some(false)), Change::Skip(_)));
+        assert!(matches!(
+            change("full.txt", Some(true)),
+            Change::Replace { backup, .. } if backup == path("full.txt.bak")
+        ));
+
+        let step = plan_file(path("full.txt"), "file", "input".into(), Some(true)).unwrap();
+        assert!(matches!(step.change, Change::Skip("already exists")));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn backs_up_replaced_files() {
+        let dir = temp_dir("backup");
+        let path = dir.join("01.rs").to_string_lossy().to_string();
+        fs::write(&path, "old").unwrap();
+
+        let step = plan_file(path.clone(), "module file", "new".into(), Some(true)).unwrap();
+        apply(&[step]).unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+        assert_eq!(fs::read_to_string(format!("{path}.bak")).unwrap(), "old");
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn reverts_applied_steps_on_failure() {
+        let dir = temp_dir("revert");
+        let path = |name: &str| dir.join(name).to_string_lossy().to_string();
+        fs::write(path("01.rs"), "old").unwrap();
+
+        let steps = [
+            Step {
+                path: path("inputs"),
+                label: "directory".into(),
+                change: Change::CreateDir,
+            },
+            plan_file(path("01.rs"), "module file", "new".into(), Some(true)).unwrap(),
+            plan_file(path("inputs/01.txt"), "input file", String::new(), None).unwrap(),
+            Step {
+                path: path("missing/01.txt"),
+                label: "example file".into(),
+                change: Change::Create(String::new()),
+            },
+        ];
+
+        assert!(apply(&steps).is_err());
+        assert_eq!(fs::read_to_string(path("01.rs")).unwrap(), "old");
+        assert_eq!(fs::exists(path("01.rs.bak")).unwrap(), false);
+        assert_eq!(fs::exists(path("inputs")).unwrap(), false);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn keeps_existing_files_when_writing_fails() {
+        let dir = temp_dir("write");
+        let path = |name: &str| dir.join(name).to_string_lossy().to_string();
+        fs::write(path("01.rs"), "old").unwrap();
+        fs::write(path("Cargo.toml"), "[package]").unwrap();
+
+        let steps = [
+            plan_file(path("01.rs"), "module file", "new".into(), Some(true)).unwrap(),
+            Step {
+                path: path("Cargo.toml"),
+                label: "register bin".into(),
+                change: Change::Update("[package]\n[[bin]]".into()),
+            },
+        ];
+
+        // a directory in place of the temporary file makes writing fail.
+        fs::create_dir(temp_path(&path("Cargo.toml"))).unwrap();
+
+        assert!(apply(&steps).is_err());
+        assert_eq!(fs::read_to_string(path("01.rs")).unwrap(), "old");
+        assert_eq!(fs::read_to_string(path("Cargo.toml")).unwrap(), "[package]");
+        assert_eq!(fs::exists(path("01.rs.bak")).unwrap(), false);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
 }
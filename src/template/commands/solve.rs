@@ -1,9 +1,23 @@
 use std::process::{Command, Stdio};
 
-use crate::template::Day;
+use crate::template::{paths, runner::BenchConfig, Day, Year};
 
-pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>) {
-    let mut cmd_args = vec!["run".to_string(), "--bin".to_string(), day.to_string()];
+#[allow(clippy::too_many_arguments)]
+pub fn handle(
+    year: Option<Year>,
+    day: Day,
+    release: bool,
+    dhat: bool,
+    alloc_stats: bool,
+    submit_part: Option<u8>,
+    time: bool,
+    bench_config: &BenchConfig,
+) {
+    let mut cmd_args = vec![
+        "run".to_string(),
+        "--bin".to_string(),
+        paths::bin_name(year, day),
+    ];
 
     if dhat {
         cmd_args.extend([
@@ -16,6 +30,10 @@ pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>) {
         cmd_args.push("--release".to_string());
     }
 
+    if alloc_stats {
+        cmd_args.extend(["--features".to_string(), "alloc-stats".to_string()]);
+    }
+
     cmd_args.push("--".to_string());
 
     if let Some(submit_part) = submit_part {
@@ -23,6 +41,13 @@ pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>) {
         cmd_args.push(submit_part.to_string());
     }
 
+    if time {
+        cmd_args.push("--time".to_string());
+    }
+
+    // sampling options are ignored by the child unless `--time` is set.
+    cmd_args.extend(bench_config.to_args());
+
     let mut cmd = Command::new("cargo")
         .args(&cmd_args)
         .stdout(Stdio::inherit())
@@ -0,0 +1,13 @@
+use std::process;
+
+use crate::template::{readme_stars, Year};
+
+pub fn handle(year: Option<Year>) {
+    match readme_stars::update(year) {
+        Ok(total) => println!("🎄 Updated ⭐️ progress in the readme ({total} stars)."),
+        Err(e) => {
+            eprintln!("Failed to update ⭐️ progress: {e}");
+            process::exit(1);
+        }
+    }
+}
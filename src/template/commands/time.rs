@@ -1,19 +1,52 @@
-use std::collections::HashSet;
+use std::{collections::HashSet, process};
 
-use crate::template::run_multi::run_multi;
+use crate::template::budgets::Budgets;
+use crate::template::environment::Environment;
+use crate::template::export::Export;
+use crate::template::history::{History, Snapshot};
+use crate::template::run_multi::{child_commands, run_multi};
+use crate::template::runner::BenchConfig;
 use crate::template::timings::Timings;
-use crate::template::{all_days, readme_benchmarks, Day};
+use crate::template::{all_days_in, paths, readme_benchmarks, Day, Year};
 
-pub fn handle(day: Option<Day>, run_all: bool, store: bool) {
-    let stored_timings = Timings::read_from_file();
+#[allow(clippy::too_many_arguments)]
+pub fn handle(
+    year: Option<Year>,
+    day: Option<Day>,
+    run_all: bool,
+    store: bool,
+    record_history: bool,
+    export: Option<&Export>,
+    check: bool,
+    show_memory: bool,
+    alloc_stats: bool,
+    bench_config: &BenchConfig,
+) {
+    let stored_timings = Timings::read_from_file(year);
+
+    let budgets = match Budgets::read_from_file(year) {
+        Ok(budgets) => budgets,
+        Err(e) => {
+            eprintln!("Failed to read time budgets: {e}");
+            process::exit(1);
+        }
+    };
+
+    if check && budgets.is_none() {
+        eprintln!(
+            "No time budgets configured, add them to `{}`.",
+            paths::budgets_path(year)
+        );
+        process::exit(1);
+    }
 
     let days_to_run = day.map_or_else(
         || {
             if run_all {
-                all_days().collect()
+                all_days_in(year).collect()
             } else {
                 // when the `--all` flag is not set, filter out days that are fully benched.
-                all_days()
+                all_days_in(year)
                     .filter(|day| !stored_timings.is_day_complete(*day))
                     .collect()
             }
@@ -21,20 +54,77 @@ pub fn handle(day: Option<Day>, run_all: bool, store: bool) {
         |day| HashSet::from([day]),
     );
 
-    let timings = run_multi(&days_to_run, true, true).unwrap();
+    let is_release = true;
+    let mut timings = run_multi(
+        year,
+        &days_to_run,
+        is_release,
+        true,
+        alloc_stats,
+        bench_config,
+    )
+    .unwrap();
+
+    if !store && !check && export.is_none() {
+        return;
+    }
+
+    timings.environment = Some(Environment::capture(
+        child_commands::profile(is_release),
+        bench_config,
+    ));
+    let merged_timings = stored_timings.merge(&timings);
+
+    let overruns = budgets
+        .map(|budgets| budgets.check(&merged_timings))
+        .unwrap_or_default();
+
+    if let Some(export) = export {
+        println!();
+        match export.write(year, &merged_timings) {
+            Ok(path) => println!("Exported timings to `{path}`."),
+            Err(e) => eprintln!("Failed to export timings: {e}"),
+        }
+    }
 
     if store {
-        let merged_timings = stored_timings.merge(&timings);
-        merged_timings.store_file().unwrap();
+        merged_timings.store_file(year).unwrap();
+
+        let mut history = History::read_from_file(year);
+        if record_history {
+            let label = merged_timings
+                .environment
+                .as_ref()
+                .and_then(|environment| environment.git_commit.clone())
+                .unwrap_or_else(|| format!("#{}", history.data.len() + 1));
+            history.push(Snapshot {
+                label,
+                total_nanos: merged_timings.total_millis() * 1_000_000_f64,
+            });
+            history.store_file(year).unwrap();
+        }
 
         println!();
-        match readme_benchmarks::update(merged_timings) {
+        match readme_benchmarks::update(year, merged_timings, &history, &overruns, show_memory) {
             Ok(()) => {
                 println!("Stored updated benchmarks.");
             }
-            Err(_) => {
-                eprintln!("Failed to store updated benchmarks.");
+            Err(e) => {
+                eprintln!("Failed to store updated benchmarks: {e}");
+            }
+        }
+    }
+
+    if check {
+        println!();
+        if overruns.is_empty() {
+            println!("All benchmarks are within their time budgets.");
+        } else {
+            eprintln!("Over the time budget:");
+            for overrun in &overruns {
+                eprintln!("  {overrun}");
             }
+            process::exit(1);
         }
     }
 }
@@ -0,0 +1,159 @@
+use std::{
+    io::{stdout, Write},
+    path::Path,
+    process, thread,
+    time::{Duration, SystemTime},
+};
+
+use crate::template::{
+    all_days_in,
+    commands::{download, read, scaffold},
+    paths, Day, Year,
+};
+
+/// How often and how long to retry the download right after the unlock.
+const DOWNLOAD_RETRIES: u32 = 5;
+const DOWNLOAD_RETRY_DELAY: Duration = Duration::from_secs(5);
+
+/// Scaffold, download and read the current day.
+/// With `wait`, wait for the next day of the event to unlock first.
+pub fn handle(year: Option<Year>, wait: bool) {
+    if wait {
+        handle_wait(year);
+        return;
+    }
+
+    match Day::today() {
+        Some(day) => setup(year, day, 0),
+        None => {
+            eprintln!(
+                "`today` command can only be run between the 1st and \
+                the 25th of december. Please use `scaffold` with a specific day, \
+                or pass `--wait` to wait for the next unlock."
+            );
+            process::exit(1)
+        }
+    }
+}
+
+/// Wait for the next unlock of the selected event.
+/// Without `--year`, this is the current or upcoming event, as `AOC_YEAR` may point to an event that is over.
+fn handle_wait(year: Option<Year>) {
+    let Some(event_year) = year.or_else(Year::current) else {
+        eprintln!("Could not determine the year of the event.");
+        process::exit(1);
+    };
+
+    // today's puzzle is already out, e.g. when waiting is started after the unlock.
+    let today = Day::today()
+        .filter(|_| Year::current() == Some(event_year))
+        .filter(|day| !Path::new(&paths::bin_path(target_year(year, event_year), *day)).exists());
+    if let Some(day) = today {
+        setup(target_year(year, event_year), day, 0);
+        return;
+    }
+
+    let next = match year {
+        Some(year) => Day::next_unlock(year).map(|day| (year, day)),
+        None => upcoming_unlock(event_year, SystemTime::now()),
+    };
+    let Some((event_year, day)) = next else {
+        eprintln!("All days of {event_year} are unlocked already.");
+        process::exit(1);
+    };
+    wait_for_unlock(day, day.unlock_time(event_year.into_inner()));
+    setup(target_year(year, event_year), day, DOWNLOAD_RETRIES);
+}
+
+/// The next day to unlock after `now`, in the event of `year` or, once that is over, in the following one.
+fn upcoming_unlock(year: Year, now: SystemTime) -> Option<(Year, Day)> {
+    [Some(year), Year::new(year.into_inner() + 1)]
+        .into_iter()
+        .flatten()
+        .find_map(|year| {
+            all_days_in(Some(year))
+                .find(|day| day.unlock_time(year.into_inner()) > now)
+                .map(|day| (year, day))
+        })
+}
+
+/// The year to set up a day of an event in.
+/// Days of an event other than the one in `AOC_YEAR` go to the directory of their year.
+fn target_year(year: Option<Year>, event_year: Year) -> Option<Year> {
+    year.or_else(|| Some(event_year).filter(|year| Year::from_env() != Some(*year)))
+}
+
+fn setup(year: Option<Year>, day: Day, retries: u32) {
+    scaffold::handle(
+        year,
+        day,
+        false,
+        false,
+        scaffold::DEFAULT_TEMPLATE,
+        None,
+        None,
+    );
+    download::handle_with_retries(year, day, retries, DOWNLOAD_RETRY_DELAY);
+    read::handle(year, day, false);
+}
+
+/// Show a countdown until the given unlock time is reached.
+fn wait_for_unlock(day: Day, unlock_time: SystemTime) {
+    while let Ok(remaining) = unlock_time.duration_since(SystemTime::now()) {
+        print!(
+            "\r⏳ Day {day} unlocks in {}  ",
+            format_countdown(remaining)
+        );
+        let _ = stdout().flush();
+        thread::sleep(remaining.min(Duration::from_secs(1)));
+    }
+    println!("\r🎄 Day {day} is unlocked!              ");
+}
+
+fn format_countdown(remaining: Duration) -> String {
+    // round up, so the countdown reaches zero exactly at the unlock.
+    let seconds = remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0);
+    let (days, seconds) = (seconds / 86400, seconds % 86400);
+    let time = format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        seconds % 3600 / 60,
+        seconds % 60
+    );
+
+    if days > 0 {
+        format!("{days}d {time}")
+    } else {
+        time
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{format_countdown, upcoming_unlock};
+    use crate::{day, template::Year};
+    use std::time::Duration;
+
+    #[test]
+    fn formats_countdown() {
+        assert_eq!(format_countdown(Duration::from_millis(500)), "00:00:01");
+        assert_eq!(format_countdown(Duration::from_secs(3725)), "01:02:05");
+        assert_eq!(format_countdown(Duration::from_secs(90061)), "1d 01:01:01");
+    }
+
+    #[test]
+    fn finds_upcoming_unlock() {
+        let year = Year::new(2024).unwrap();
+        let during = day!(3).unlock_time(2024) + Duration::from_secs(60);
+        assert_eq!(upcoming_unlock(year, during), Some((year, day!(4))));
+
+        // the event is over, so the next one is pending.
+        let after = day!(25).unlock_time(2024) + Duration::from_secs(86400 * 10);
+        assert_eq!(
+            upcoming_unlock(year, after),
+            Some((Year::new(2025).unwrap(), day!(1)))
+        );
+    }
+}
@@ -0,0 +1,546 @@
+//! Interactive terminal dashboard with a calendar of all days.
+//! Each action runs the `commands::*` handler of its subcommand in a child process of this binary.
+//! The handlers print to stdout and exit the process on errors, so calling them in-process could
+//! neither capture their output nor survive a failing command. Output of the child is collected
+//! into a scrollable pane.
+
+use std::{
+    env, fs,
+    io::{self, BufRead, BufReader, Read, Write},
+    path::Path,
+    process::{self, Command, Stdio},
+    sync::mpsc::{self, Receiver, Sender},
+    thread,
+    time::Duration,
+};
+
+use crossterm::{
+    cursor::{Hide, MoveTo, Show},
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    execute, queue,
+    style::{Print, Stylize},
+    terminal::{
+        self, disable_raw_mode, enable_raw_mode, Clear, ClearType, EnterAlternateScreen,
+        LeaveAlternateScreen,
+    },
+};
+use unicode_width::UnicodeWidthChar;
+
+use crate::template::timings::Timings;
+use crate::template::{all_days_in, paths, puzzle_info, Day, Year};
+
+/// Days per row of the calendar.
+const COLUMNS: usize = 5;
+const CELL_WIDTH: usize = 14;
+/// How long to wait for key presses before checking for command output.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// What is known about a day from local files.
+#[derive(Clone, Debug, PartialEq)]
+struct DayStatus {
+    day: Day,
+    scaffolded: bool,
+    input: bool,
+    stars: usize,
+    benched: bool,
+}
+
+impl DayStatus {
+    fn read(year: Option<Year>, day: Day, timings: &Timings) -> Self {
+        DayStatus {
+            day,
+            scaffolded: Path::new(&paths::bin_path(year, day)).exists(),
+            input: Path::new(&paths::input_path(year, day)).exists(),
+            stars: fs::read_to_string(paths::puzzle_path(year, day))
+                .map_or(0, |puzzle| puzzle_info::answers(&puzzle).len()),
+            benched: timings.data.iter().any(|timing| timing.day == day),
+        }
+    }
+
+    /// The text of a calendar cell, e.g. ` 01 S I ** B `.
+    fn cell(&self) -> String {
+        let flag = |present: bool, c: char| if present { c } else { '.' };
+        format!(
+            " {} {} {} {:.<2} {} ",
+            self.day,
+            flag(self.scaffolded, 'S'),
+            flag(self.input, 'I'),
+            "*".repeat(self.stars.min(2)),
+            flag(self.benched, 'B')
+        )
+    }
+}
+
+/// Output of a running command.
+enum Message {
+    Line(String),
+    Done(Option<i32>),
+}
+
+/// A command that is offered for the selected day.
+#[derive(Clone, Copy)]
+enum Action {
+    Run,
+    Time,
+    Read,
+    Submit(u8),
+}
+
+impl Action {
+    fn args(self, year: Option<Year>, day: Day) -> Vec<String> {
+        let mut args: Vec<String> = match self {
+            Action::Run => vec!["solve".into(), day.to_string()],
+            Action::Time => vec!["time".into(), day.to_string()],
+            Action::Read => vec!["read".into(), day.to_string()],
+            Action::Submit(part) => vec![
+                "solve".into(),
+                day.to_string(),
+                "--submit".into(),
+                part.to_string(),
+            ],
+        };
+        if let Some(year) = year {
+            args.extend(["--year".into(), year.to_string()]);
+        }
+        args
+    }
+}
+
+/// Remove terminal escape sequences, e.g. colors, so that output can be laid out in the pane.
+/// Like in a terminal, a carriage return starts the line over, e.g. when the runner replaces its progress.
+fn strip_ansi(line: &str) -> String {
+    let mut out = String::new();
+    let mut chars = line.trim_end_matches('\r').chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\x1b' => {
+                if chars.peek() == Some(&'[') {
+                    chars.next();
+                    // control sequences end with a letter, e.g. `\x1b[1;32m`.
+                    for c in chars.by_ref() {
+                        if c.is_ascii_alphabetic() || c == '~' {
+                            break;
+                        }
+                    }
+                }
+            }
+            '\r' => out.clear(),
+            '\t' => out.push_str("    "),
+            c => out.push(c),
+        }
+    }
+
+    out
+}
+
+/// The lines of the output pane, `scroll` lines above the end of the output.
+fn visible_lines(output: &[String], height: usize, scroll: usize) -> &[String] {
+    let end = output.len().saturating_sub(scroll);
+    &output[end.saturating_sub(height)..end]
+}
+
+/// Move the selection within the calendar grid, staying on the same position if the move leaves it.
+fn move_selection(selected: usize, count: usize, key: KeyCode) -> usize {
+    let target = match key {
+        KeyCode::Left | KeyCode::Char('h') => selected.checked_sub(1),
+        KeyCode::Right | KeyCode::Char('l') => Some(selected + 1),
+        KeyCode::Up | KeyCode::Char('k') => selected.checked_sub(COLUMNS),
+        KeyCode::Down | KeyCode::Char('j') => Some(selected + COLUMNS),
+        _ => None,
+    };
+    target.filter(|target| *target < count).unwrap_or(selected)
+}
+
+/// Truncate or pad a line to a width in terminal columns, so wide characters such as emoji fit.
+fn pad(s: &str, width: usize) -> String {
+    let mut padded = String::new();
+    let mut len = 0;
+    for c in s.chars() {
+        let char_width = c.width().unwrap_or(0);
+        if len + char_width > width {
+            break;
+        }
+        padded.push(c);
+        len += char_width;
+    }
+    padded.push_str(&" ".repeat(width - len));
+    padded
+}
+
+struct App {
+    year: Option<Year>,
+    days: Vec<DayStatus>,
+    selected: usize,
+    output: Vec<String>,
+    scroll: usize,
+    status: String,
+    /// The command that is currently running, if any.
+    running: Option<String>,
+    /// Set while waiting for the part to submit.
+    choosing_part: bool,
+    sender: Sender<Message>,
+    receiver: Receiver<Message>,
+}
+
+impl App {
+    fn new(year: Option<Year>) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let mut app = App {
+            year,
+            days: vec![],
+            selected: 0,
+            output: vec![],
+            scroll: 0,
+            status: "Select a day and press a key to run a command.".into(),
+            running: None,
+            choosing_part: false,
+            sender,
+            receiver,
+        };
+        app.refresh();
+        #[cfg(feature = "today")]
+        if let Some(today) = Day::today() {
+            app.selected = app
+                .days
+                .iter()
+                .position(|status| status.day == today)
+                .unwrap_or(0);
+        }
+        app
+    }
+
+    fn refresh(&mut self) {
+        let timings = Timings::read_from_file(self.year);
+        self.days = all_days_in(self.year)
+            .map(|day| DayStatus::read(self.year, day, &timings))
+            .collect();
+    }
+
+    fn selected_day(&self) -> Day {
+        self.days[self.selected].day
+    }
+
+    /// Run a command for the selected day and stream its output to the pane.
+    fn start(&mut self, action: Action) {
+        if let Some(running) = &self.running {
+            self.status = format!("Wait for `{running}` to finish.");
+            return;
+        }
+
+        let args = action.args(self.year, self.selected_day());
+        let label = args.join(" ");
+
+        let child = env::current_exe().and_then(|exe| {
+            Command::new(exe)
+                .args(&args)
+                .stdin(Stdio::null())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .spawn()
+        });
+
+        let mut child = match child {
+            Ok(child) => child,
+            Err(e) => {
+                self.status = format!("Failed to run `{label}`: {e}");
+                return;
+            }
+        };
+
+        self.output.push(format!("$ {label}"));
+        self.scroll = 0;
+        self.status = format!("Running `{label}`...");
+        self.running = Some(label);
+
+        let forward = |stream: Box<dyn Read + Send>, sender: Sender<Message>| {
+            thread::spawn(move || {
+                for line in BufReader::new(stream).lines().map_while(Result::ok) {
+                    if sender.send(Message::Line(strip_ansi(&line))).is_err() {
+                        break;
+                    }
+                }
+            })
+        };
+
+        let stdout = forward(Box::new(child.stdout.take().unwrap()), self.sender.clone());
+        let stderr = forward(Box::new(child.stderr.take().unwrap()), self.sender.clone());
+        let sender = self.sender.clone();
+
+        thread::spawn(move || {
+            let _ = stdout.join();
+            let _ = stderr.join();
+            let code = child.wait().ok().and_then(|status| status.code());
+            let _ = sender.send(Message::Done(code));
+        });
+    }
+
+    /// Collect the output of the running command. Returns `true` if anything changed.
+    fn receive(&mut self) -> bool {
+        let mut changed = false;
+
+        while let Ok(message) = self.receiver.try_recv() {
+            changed = true;
+            match message {
+                Message::Line(line) => {
+                    self.output.push(line);
+                    // keep the visible lines in place while scrolled up.
+                    if self.scroll > 0 {
+                        self.scroll += 1;
+                    }
+                }
+                Message::Done(code) => {
+                    let label = self.running.take().unwrap_or_default();
+                    self.status = match code {
+                        Some(0) => format!("`{label}` finished."),
+                        Some(code) => format!("`{label}` failed with exit code {code}."),
+                        None => format!("`{label}` was terminated."),
+                    };
+                    self.output.push(String::new());
+                    self.refresh();
+                }
+            }
+        }
+
+        changed
+    }
+
+    /// Handle a key press. Returns `false` if the dashboard should be closed.
+    fn handle_key(&mut self, key: KeyEvent, pane_height: usize) -> bool {
+        if self.choosing_part {
+            self.choosing_part = false;
+            match key.code {
+                KeyCode::Char(c @ '1'..='9') => self.start(Action::Submit(c as u8 - b'0')),
+                _ => self.status = "Submission cancelled.".into(),
+            }
+            return true;
+        }
+
+        let max_scroll = self.output.len().saturating_sub(pane_height);
+
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return false,
+            KeyCode::Char('r') => self.start(Action::Run),
+            KeyCode::Char('t') => self.start(Action::Time),
+            KeyCode::Char('v') => self.start(Action::Read),
+            KeyCode::Char('s') => {
+                self.choosing_part = true;
+                self.status = format!(
+                    "Submit which part of day {}? Press 1 or 2, any other key cancels.",
+                    self.selected_day().into_inner()
+                );
+            }
+            KeyCode::PageUp => self.scroll = (self.scroll + pane_height).min(max_scroll),
+            KeyCode::PageDown => self.scroll = self.scroll.saturating_sub(pane_height),
+            KeyCode::Home => self.scroll = max_scroll,
+            KeyCode::End => self.scroll = 0,
+            KeyCode::Char('x') => {
+                self.output.clear();
+                self.scroll = 0;
+            }
+            code => self.selected = move_selection(self.selected, self.days.len(), code),
+        }
+
+        true
+    }
+
+    /// Lines above the output pane: title, calendar and legend.
+    fn header_height(&self) -> usize {
+        3 + self.days.len().div_ceil(COLUMNS) + 2
+    }
+
+    fn draw(&self, out: &mut impl Write) -> io::Result<()> {
+        let (width, height) = terminal::size()?;
+        let (width, height) = (usize::from(width), usize::from(height));
+
+        let title = match self.year.or_else(Year::from_env) {
+            Some(year) => format!("Advent of Code {year}"),
+            None => "Advent of Code".into(),
+        };
+
+        queue!(out, MoveTo(0, 0), Clear(ClearType::All))?;
+        queue!(
+            out,
+            Print(pad(&title, width).bold().green()),
+            Print("\r\n\r\n")
+        )?;
+
+        for (i, days) in self.days.chunks(COLUMNS).enumerate() {
+            for (j, status) in days.iter().enumerate() {
+                let cell = pad(&status.cell(), CELL_WIDTH);
+                let cell = if i * COLUMNS + j == self.selected {
+                    cell.black().on_green()
+                } else if status.stars >= 2 {
+                    cell.yellow()
+                } else if status.scaffolded {
+                    cell.white()
+                } else {
+                    cell.dark_grey()
+                };
+                queue!(out, Print(cell))?;
+            }
+            queue!(out, Print("\r\n"))?;
+        }
+
+        let legend = "S scaffolded  I input  * answer  B benched";
+        queue!(
+            out,
+            Print("\r\n"),
+            Print(pad(legend, width).dark_grey()),
+            Print("\r\n")
+        )?;
+
+        let pane_height = height.saturating_sub(self.header_height() + 3);
+        let scroll_info = if self.scroll > 0 {
+            format!(" (scrolled up {} lines)", self.scroll)
+        } else {
+            String::new()
+        };
+        let rule = format!("── Output{scroll_info} ");
+        let rule = format!(
+            "{rule}{}",
+            "─".repeat(width.saturating_sub(rule.chars().count()))
+        );
+        queue!(out, Print(rule.dark_grey()), Print("\r\n"))?;
+
+        let lines = visible_lines(&self.output, pane_height, self.scroll);
+        for line in lines {
+            queue!(out, Print(pad(line, width)), Print("\r\n"))?;
+        }
+        for _ in lines.len()..pane_height {
+            queue!(out, Print("\r\n"))?;
+        }
+
+        let keys = "←↑↓→ select · r run · t time · v read · s submit · PgUp/PgDn scroll · x clear · q quit";
+        queue!(
+            out,
+            Print(pad(&self.status, width).bold()),
+            Print("\r\n"),
+            Print(pad(keys, width).dark_grey())
+        )?;
+
+        out.flush()
+    }
+
+    fn pane_height(&self) -> io::Result<usize> {
+        let (_, height) = terminal::size()?;
+        Ok(usize::from(height).saturating_sub(self.header_height() + 3))
+    }
+}
+
+/// Restores the terminal when the dashboard is closed, also if it panics.
+struct Screen;
+
+impl Screen {
+    fn enter() -> io::Result<Self> {
+        enable_raw_mode()?;
+        execute!(io::stdout(), EnterAlternateScreen, Hide)?;
+        Ok(Screen)
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), Show, LeaveAlternateScreen);
+        let _ = disable_raw_mode();
+    }
+}
+
+fn run(year: Option<Year>) -> io::Result<()> {
+    let _screen = Screen::enter()?;
+    let mut stdout = io::stdout();
+    let mut app = App::new(year);
+    let mut dirty = true;
+
+    loop {
+        if dirty {
+            app.draw(&mut stdout)?;
+            dirty = false;
+        }
+
+        if event::poll(POLL_INTERVAL)? {
+            match event::read()? {
+                Event::Key(key) if key.kind == KeyEventKind::Press => {
+                    if !app.handle_key(key, app.pane_height()?) {
+                        return Ok(());
+                    }
+                    dirty = true;
+                }
+                Event::Resize(_, _) => dirty = true,
+                _ => {}
+            }
+        }
+
+        dirty |= app.receive();
+    }
+}
+
+pub fn handle(year: Option<Year>) {
+    if let Err(e) = run(year) {
+        eprintln!("Failed to run the dashboard: {e}");
+        process::exit(1);
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{move_selection, pad, strip_ansi, visible_lines, DayStatus};
+    use crate::day;
+    use crossterm::event::KeyCode;
+
+    #[test]
+    fn renders_cells() {
+        let mut status = DayStatus {
+            day: day!(3),
+            scaffolded: true,
+            input: false,
+            stars: 1,
+            benched: false,
+        };
+        assert_eq!(status.cell(), " 03 S . *. . ");
+
+        status.input = true;
+        status.stars = 2;
+        status.benched = true;
+        assert_eq!(status.cell(), " 03 S I ** B ");
+    }
+
+    #[test]
+    fn pads_by_display_width() {
+        assert_eq!(pad("Day 1", 7), "Day 1  ");
+        assert_eq!(pad("🎄 ⭐ 2023", 8), "🎄 ⭐ 20");
+        assert_eq!(pad("⭐⭐", 3), "⭐ ");
+    }
+
+    #[test]
+    fn strips_escape_sequences() {
+        assert_eq!(strip_ansi("\x1b[1;32mPart 1:\x1b[0m 42\r"), "Part 1: 42");
+        assert_eq!(
+            strip_ansi("Part 1: 42\rPart 1: 42 (1.2ms)"),
+            "Part 1: 42 (1.2ms)"
+        );
+        assert_eq!(strip_ansi("\ttab"), "    tab");
+    }
+
+    #[test]
+    fn scrolls_output() {
+        let output: Vec<String> = (0..10).map(|n| n.to_string()).collect();
+        assert_eq!(visible_lines(&output, 3, 0), ["7", "8", "9"]);
+        assert_eq!(visible_lines(&output, 3, 2), ["5", "6", "7"]);
+        assert_eq!(visible_lines(&output, 20, 0).len(), 10);
+        assert_eq!(visible_lines(&output, 3, 20).len(), 0);
+    }
+
+    #[test]
+    fn moves_selection_within_grid() {
+        assert_eq!(move_selection(0, 12, KeyCode::Left), 0);
+        assert_eq!(move_selection(0, 12, KeyCode::Right), 1);
+        assert_eq!(move_selection(1, 12, KeyCode::Down), 6);
+        assert_eq!(move_selection(8, 12, KeyCode::Down), 8);
+        assert_eq!(move_selection(6, 12, KeyCode::Up), 1);
+        assert_eq!(move_selection(11, 12, KeyCode::Right), 11);
+    }
+}
@@ -1,20 +1,23 @@
 use std::error::Error;
 use std::fmt::Display;
 use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 #[cfg(feature = "today")]
 use chrono::{Datelike, FixedOffset, Utc};
 
-#[cfg(feature = "today")]
+use crate::template::{Year, MAX_DAYS};
+
 const SERVER_UTC_OFFSET: i32 = -5;
 
 /// A valid day number of advent (i.e. an integer in range 1 to 25).
+/// Events with a shorter calendar have fewer days, see [`Year::days`].
 ///
 /// # Display
 /// This value displays as a two digit number.
 ///
 /// ```
-/// # use advent_of_code::Day;
+/// # use advent_of_code::template::Day;
 /// let day = Day::new(8).unwrap();
 /// assert_eq!(day.to_string(), "08")
 /// ```
@@ -25,12 +28,36 @@ impl Day {
     /// Creates a [`Day`] from the provided value if it's in the valid range,
     /// returns [`None`] otherwise.
     pub fn new(day: u8) -> Option<Self> {
-        if day == 0 || day > 25 {
+        if day == 0 || day > MAX_DAYS {
+            return None;
+        }
+        Some(Self(day))
+    }
+
+    /// Creates a [`Day`] from the provided value if it's part of the calendar of a year,
+    /// returns [`None`] otherwise.
+    pub fn new_in(day: u8, year: Year) -> Option<Self> {
+        if day == 0 || day > year.days() {
             return None;
         }
         Some(Self(day))
     }
 
+    /// Parses a [`Day`] that is part of the calendar of a year.
+    /// Without a year, the year configured in `AOC_YEAR` is used. If that is not set either, any day from 1 to 25 is valid.
+    pub fn parse_in(s: &str, year: Option<Year>) -> Result<Self, DayFromStrError> {
+        let year = year.or_else(Year::from_env);
+        let max = year.map_or(MAX_DAYS, Year::days);
+        let error = DayFromStrError { max, year };
+
+        let day = s.parse().map_err(|_| error)?;
+        match year {
+            Some(year) => Self::new_in(day, year),
+            None => Self::new(day),
+        }
+        .ok_or(error)
+    }
+
     // Not part of the public API
     #[doc(hidden)]
     pub const fn __new_unchecked(day: u8) -> Self {
@@ -41,22 +68,66 @@ impl Day {
     pub fn into_inner(self) -> u8 {
         self.0
     }
+
+    /// Returns the point in time at which the puzzle for this day unlocks in a given year.
+    /// Puzzles unlock at midnight server time.
+    pub fn unlock_time(self, year: u16) -> SystemTime {
+        let days = days_from_civil(i64::from(year), 12, i64::from(self.0));
+        let seconds = days * 86400 - i64::from(SERVER_UTC_OFFSET) * 3600;
+        UNIX_EPOCH + Duration::from_secs(u64::try_from(seconds).unwrap_or_default())
+    }
+
+    /// Returns whether the puzzle for this day is already unlocked in a given year.
+    pub fn is_unlocked(self, year: u16) -> bool {
+        SystemTime::now() >= self.unlock_time(year)
+    }
+
+    /// Returns the next day of an event to unlock, or `None` if all of its days are unlocked.
+    pub fn next_unlock(year: Year) -> Option<Self> {
+        all_days_in(Some(year)).find(|day| !day.is_unlocked(year.into_inner()))
+    }
+}
+
+/// Number of days since the unix epoch for a date in the proleptic gregorian calendar.
+/// See: http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
 }
 
 #[cfg(feature = "today")]
 impl Day {
-    /// Returns the current day if it's between the 1st and the 25th of december, `None` otherwise.
+    /// Returns the current day if it's part of this year's calendar, e.g. between the 1st and the 25th of december.
+    /// Returns `None` otherwise.
     pub fn today() -> Option<Self> {
-        let offset = FixedOffset::east_opt(SERVER_UTC_OFFSET * 3600)?;
-        let today = Utc::now().with_timezone(&offset);
-        if today.month() == 12 && today.day() <= 25 {
-            Self::new(u8::try_from(today.day()).ok()?)
+        let today = server_now()?;
+        let year = Year::new(u16::try_from(today.year()).ok()?)?;
+        if today.month() == 12 {
+            Self::new_in(u8::try_from(today.day()).ok()?, year)
         } else {
             None
         }
     }
 }
 
+#[cfg(feature = "today")]
+impl Year {
+    /// Returns the current year in server time.
+    pub fn current() -> Option<Self> {
+        Self::new(u16::try_from(server_now()?.year()).ok()?)
+    }
+}
+
+#[cfg(feature = "today")]
+fn server_now() -> Option<chrono::DateTime<FixedOffset>> {
+    let offset = FixedOffset::east_opt(SERVER_UTC_OFFSET * 3600)?;
+    Some(Utc::now().with_timezone(&offset))
+}
+
 impl Display for Day {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{:02}", self.0)
@@ -80,40 +151,74 @@ impl PartialOrd<u8> for Day {
 impl FromStr for Day {
     type Err = DayFromStrError;
 
+    /// Parses any day from 1 to 25. Use [`Day::parse_in`] to check a day against the calendar of a year.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let day = s.parse().map_err(|_| DayFromStrError)?;
-        Self::new(day).ok_or(DayFromStrError)
+        let error = DayFromStrError {
+            max: MAX_DAYS,
+            year: None,
+        };
+        s.parse().ok().and_then(Self::new).ok_or(error)
     }
 }
 
 /// An error which can be returned when parsing a [`Day`].
-#[derive(Debug)]
-pub struct DayFromStrError;
+#[derive(Debug, Clone, Copy)]
+pub struct DayFromStrError {
+    max: u8,
+    year: Option<Year>,
+}
 
 impl Error for DayFromStrError {}
 
 impl Display for DayFromStrError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str("expecting a day number between 1 and 25")
+        match self.year {
+            Some(year) => write!(
+                f,
+                "expecting a day number between 1 and {} for {year}",
+                self.max
+            ),
+            None => write!(f, "expecting a day number between 1 and {}", self.max),
+        }
     }
 }
 
 /* -------------------------------------------------------------------------- */
 
-/// An iterator that yields every day of advent from the 1st to the 25th.
+/// An iterator that yields every day of the year configured in `AOC_YEAR`, e.g. from the 1st to the 25th.
 pub fn all_days() -> AllDays {
     AllDays::new()
 }
 
-/// An iterator that yields every day of advent from the 1st to the 25th.
+/// An iterator that yields every day of a year. Without a year, this is the same as [`all_days`].
+pub fn all_days_in(year: Option<Year>) -> AllDays {
+    match year {
+        Some(year) => AllDays::for_year(year),
+        None => AllDays::new(),
+    }
+}
+
+/// An iterator that yields every day of advent from the 1st to the last day of a year's calendar.
 pub struct AllDays {
     current: u8,
+    last: u8,
 }
 
 impl AllDays {
+    /// Yields the days of the year configured in `AOC_YEAR`, or all 25 days if it is not set.
     #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
-        Self { current: 1 }
+        Self {
+            current: 1,
+            last: Year::from_env().map_or(MAX_DAYS, Year::days),
+        }
+    }
+
+    pub fn for_year(year: Year) -> Self {
+        Self {
+            current: 1,
+            last: year.days(),
+        }
     }
 }
 
@@ -121,10 +226,10 @@ impl Iterator for AllDays {
     type Item = Day;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.current > 25 {
+        if self.current > self.last {
             return None;
         }
-        // NOTE: the iterator starts at 1 and we have verified that the value is not above 25.
+        // NOTE: the iterator starts at 1 and the calendar never has more than 25 days.
         let day = Day(self.current);
         self.current += 1;
 
@@ -135,6 +240,10 @@ impl Iterator for AllDays {
 /* -------------------------------------------------------------------------- */
 
 /// Creates a [`Day`] value in a const context.
+///
+/// Pass a year as the second parameter to validate the day against the built-in calendar of that year.
+/// The check happens at compile time, so it does not consider the `AOC_CALENDAR` override, see [`Year::days`].
+/// Without a year, any day from 1 to 25 is accepted.
 #[macro_export]
 macro_rules! day {
     ($day:expr) => {{
@@ -148,17 +257,66 @@ macro_rules! day {
         );
         $crate::template::Day::__new_unchecked($day)
     }};
+    ($day:expr, $year:expr) => {{
+        const _ASSERT: () = assert!(
+            $day != 0 && $day <= $crate::template::calendar_days($year),
+            concat!(
+                "invalid day number `",
+                $day,
+                "`, the calendar of ",
+                $year,
+                " does not have that many days"
+            ),
+        );
+        $crate::template::Day::__new_unchecked($day)
+    }};
 }
 
 /* -------------------------------------------------------------------------- */
 
 #[cfg(feature = "test_lib")]
 mod tests {
-    use super::{all_days, Day};
+    use super::{AllDays, Day};
+    use crate::template::Year;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    #[test]
+    fn validates_days_against_calendar() {
+        let year = Year::new(2025).unwrap();
+        assert_eq!(Day::new_in(12, year), Some(Day(12)));
+        assert_eq!(Day::new_in(13, year), None);
+        assert_eq!(Day::parse_in("12", Some(year)).unwrap(), Day(12));
+        assert_eq!(
+            Day::parse_in("13", Some(year)).unwrap_err().to_string(),
+            "expecting a day number between 1 and 12 for 2025"
+        );
+        assert_eq!(Day::parse_in("25", Year::new(2024)).unwrap(), Day(25));
+        assert_eq!("25".parse::<Day>().unwrap(), Day(25));
+        assert_eq!(AllDays::for_year(year).count(), 12);
+        assert_eq!(crate::day!(12, 2025), Day(12));
+    }
+
+    #[test]
+    fn unlock_time() {
+        // 2024-12-01T05:00:00Z
+        assert_eq!(
+            Day(1).unlock_time(2024),
+            UNIX_EPOCH + Duration::from_secs(1_733_029_200)
+        );
+        // 2015-12-25T05:00:00Z
+        assert_eq!(
+            Day(25).unlock_time(2015),
+            UNIX_EPOCH + Duration::from_secs(1_451_019_600)
+        );
+        assert_eq!(Day(1).is_unlocked(2015), true);
+        assert_eq!(Day(1).is_unlocked(9999), false);
+        assert_eq!(Day::next_unlock(Year::new(2015).unwrap()), None);
+        assert_eq!(Day::next_unlock(Year::new(9999).unwrap()), Some(Day(1)));
+    }
 
     #[test]
     fn all_days_iterator() {
-        let mut iter = all_days();
+        let mut iter = AllDays::for_year(Year::new(2024).unwrap());
 
         assert_eq!(iter.next(), Some(Day(1)));
         assert_eq!(iter.next(), Some(Day(2)));
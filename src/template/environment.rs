@@ -0,0 +1,213 @@
+/// Captures information about the machine and toolchain a benchmark was run with.
+use std::{collections::HashMap, env, fs, process::Command};
+use tinyjson::JsonValue;
+
+use crate::template::runner::BenchConfig;
+
+/// Describes the environment a set of timings was measured in.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Environment {
+    pub cpu_model: Option<String>,
+    pub cores: Option<usize>,
+    pub rustc_version: Option<String>,
+    pub profile: String,
+    pub target_features: Vec<String>,
+    pub git_commit: Option<String>,
+    pub sampling: Option<String>,
+}
+
+impl Environment {
+    /// Inspect the current machine. Values that can't be determined are left empty.
+    pub fn capture(profile: &str, bench_config: &BenchConfig) -> Self {
+        Environment {
+            cpu_model: fs::read_to_string("/proc/cpuinfo")
+                .ok()
+                .and_then(|s| parse_cpu_model(&s)),
+            cores: std::thread::available_parallelism()
+                .ok()
+                .map(std::num::NonZeroUsize::get),
+            rustc_version: command_output("rustc", &["--version"]),
+            profile: profile.into(),
+            target_features: get_target_features(),
+            git_commit: command_output("git", &["rev-parse", "--short", "HEAD"]),
+            sampling: Some(bench_config.to_string()),
+        }
+    }
+}
+
+fn command_output(cmd: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(cmd).args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if stdout.is_empty() {
+        None
+    } else {
+        Some(stdout)
+    }
+}
+
+fn parse_cpu_model(cpuinfo: &str) -> Option<String> {
+    cpuinfo.lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        let key = key.trim();
+        if key == "model name" || key == "Model" {
+            Some(value.trim().to_string())
+        } else {
+            None
+        }
+    })
+}
+
+/// Target features are read from `rustc --print cfg`, honoring `RUSTFLAGS` (e.g. `-C target-cpu=native`).
+fn get_target_features() -> Vec<String> {
+    let mut args: Vec<String> = env::var("RUSTFLAGS")
+        .map(|flags| flags.split_whitespace().map(String::from).collect())
+        .unwrap_or_default();
+    args.extend(["--print".into(), "cfg".into()]);
+
+    let Ok(output) = Command::new("rustc").args(&args).output() else {
+        return vec![];
+    };
+
+    parse_target_features(&String::from_utf8_lossy(&output.stdout))
+}
+
+fn parse_target_features(cfg: &str) -> Vec<String> {
+    cfg.lines()
+        .filter_map(|line| {
+            line.strip_prefix("target_feature=\"")
+                .and_then(|s| s.strip_suffix('"'))
+                .map(String::from)
+        })
+        .collect()
+}
+
+/* -------------------------------------------------------------------------- */
+
+fn optional_string(value: Option<&String>) -> JsonValue {
+    value.map_or(JsonValue::Null, |x| JsonValue::String(x.clone()))
+}
+
+impl From<&Environment> for JsonValue {
+    fn from(value: &Environment) -> Self {
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+
+        map.insert(
+            "cpu_model".into(),
+            optional_string(value.cpu_model.as_ref()),
+        );
+        #[allow(clippy::cast_precision_loss)]
+        map.insert(
+            "cores".into(),
+            value
+                .cores
+                .map_or(JsonValue::Null, |x| JsonValue::Number(x as f64)),
+        );
+        map.insert(
+            "rustc_version".into(),
+            optional_string(value.rustc_version.as_ref()),
+        );
+        map.insert("profile".into(), JsonValue::String(value.profile.clone()));
+        map.insert(
+            "target_features".into(),
+            JsonValue::Array(
+                value
+                    .target_features
+                    .iter()
+                    .cloned()
+                    .map(JsonValue::String)
+                    .collect(),
+            ),
+        );
+        map.insert(
+            "git_commit".into(),
+            optional_string(value.git_commit.as_ref()),
+        );
+        map.insert("sampling".into(), optional_string(value.sampling.as_ref()));
+
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for Environment {
+    type Error = String;
+
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected environment to be a JSON object.")?;
+
+        let get_string = |key: &str| json.get(key).and_then(|v| v.get::<String>()).cloned();
+
+        let profile =
+            get_string("profile").ok_or("Expected environment.profile to be a string.")?;
+
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let cores = json
+            .get("cores")
+            .and_then(|v| v.get::<f64>())
+            .map(|x| *x as usize);
+
+        let target_features = json
+            .get("target_features")
+            .and_then(|v| v.get::<Vec<JsonValue>>())
+            .map(|features| {
+                features
+                    .iter()
+                    .filter_map(|f| f.get::<String>().cloned())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(Environment {
+            cpu_model: get_string("cpu_model"),
+            cores,
+            rustc_version: get_string("rustc_version"),
+            profile,
+            target_features,
+            git_commit: get_string("git_commit"),
+            sampling: get_string("sampling"),
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{parse_cpu_model, parse_target_features, Environment};
+    use tinyjson::JsonValue;
+
+    #[test]
+    fn parses_cpu_model() {
+        let cpuinfo = "processor\t: 0\nvendor_id\t: AuthenticAMD\nmodel name\t: AMD Ryzen 7 5800X 8-Core Processor\n";
+        assert_eq!(
+            parse_cpu_model(cpuinfo),
+            Some("AMD Ryzen 7 5800X 8-Core Processor".into())
+        );
+        assert_eq!(parse_cpu_model("processor\t: 0\n"), None);
+    }
+
+    #[test]
+    fn parses_target_features() {
+        let cfg = "debug_assertions\ntarget_arch=\"x86_64\"\ntarget_feature=\"fxsr\"\ntarget_feature=\"sse2\"\n";
+        assert_eq!(parse_target_features(cfg), vec!["fxsr", "sse2"]);
+    }
+
+    #[test]
+    fn roundtrips_json() {
+        let env = Environment {
+            cpu_model: Some("Apple M1".into()),
+            cores: Some(8),
+            rustc_version: Some("rustc 1.83.0".into()),
+            profile: "release".into(),
+            target_features: vec!["neon".into()],
+            git_commit: None,
+            sampling: Some("100 samples".into()),
+        };
+        let json = JsonValue::from(&env);
+        assert_eq!(Environment::try_from(&json).unwrap(), env);
+    }
+}
@@ -0,0 +1,305 @@
+/// Export stored timings for use outside of the readme, e.g. in spreadsheets or dashboards.
+/// Every format lists the duration of each part both formatted and as a number of nanoseconds.
+use std::{
+    collections::HashMap,
+    env,
+    fmt::Display,
+    fs, io,
+    path::{Component, Path},
+    str::FromStr,
+};
+use tinyjson::JsonValue;
+
+use crate::template::timings::Timings;
+use crate::template::{paths, readme_benchmarks, Year};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Csv,
+    Json,
+    Markdown,
+}
+
+impl Format {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Format::Csv => "csv",
+            Format::Json => "json",
+            Format::Markdown => "md",
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "csv" => Ok(Format::Csv),
+            "json" => Ok(Format::Json),
+            "md" | "markdown" => Ok(Format::Markdown),
+            _ => Err(format!(
+                "unknown export format `{s}`, expected one of `csv`, `json` or `md`"
+            )),
+        }
+    }
+}
+
+impl Display for Format {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.extension())
+    }
+}
+
+/// An export requested with `cargo time --export <format> [--output <path>]`.
+pub struct Export {
+    pub format: Format,
+    /// Defaults to `data/benchmarks.<extension>`.
+    pub output: Option<String>,
+}
+
+impl Export {
+    /// Write the timings to the output file and return its path.
+    pub fn write(&self, year: Option<Year>, timings: &Timings) -> io::Result<String> {
+        let path = self
+            .output
+            .clone()
+            .unwrap_or_else(|| paths::export_path(year, self.format.extension()));
+
+        if let Some(dir) = Path::new(&path).parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let root = relative_root(Path::new(&path));
+        fs::write(&path, render(self.format, &root, year, timings)?)?;
+        Ok(path)
+    }
+}
+
+/// The path from the directory of an output file to the repository root, e.g. `..` for `data/benchmarks.md`.
+/// Falls back to the absolute path of the repository if the file is written outside of it.
+fn relative_root(output: &Path) -> String {
+    let Ok(cwd) = env::current_dir() else {
+        return ".".into();
+    };
+
+    let dir = cwd.join(output.parent().unwrap_or(Path::new("")));
+    let depth = dir.strip_prefix(&cwd).ok().and_then(|relative| {
+        relative
+            .components()
+            .filter(|component| *component != Component::CurDir)
+            .map(|component| matches!(component, Component::Normal(_)).then_some(()))
+            .collect::<Option<Vec<()>>>()
+            .map(|dirs| dirs.len())
+    });
+
+    match depth {
+        Some(0) => ".".into(),
+        Some(depth) => vec![".."; depth].join("/"),
+        None => cwd.display().to_string(),
+    }
+}
+
+/// Render the timings in a format. Links in the markdown document are relative to `root`, see [`relative_root`].
+pub fn render(
+    format: Format,
+    root: &str,
+    year: Option<Year>,
+    timings: &Timings,
+) -> io::Result<String> {
+    match format {
+        Format::Csv => Ok(to_csv(timings)),
+        Format::Json => to_json(year, timings)
+            .format()
+            .map(|json| json + "\n")
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string())),
+        Format::Markdown => Ok(readme_benchmarks::construct_document(root, year, timings)),
+    }
+}
+
+fn optional_cell<T: Display>(value: Option<T>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_default()
+}
+
+/// One row per part, with empty cells for values that were not measured.
+fn to_csv(timings: &Timings) -> String {
+    let mut lines: Vec<String> =
+        vec!["day,part,time,nanos,max_rss_bytes,allocations,allocated_bytes,peak_bytes".into()];
+
+    for timing in &timings.data {
+        for (i, part) in timing.parts.iter().enumerate() {
+            let allocs = part.allocs.as_ref();
+            lines.push(
+                [
+                    timing.day.into_inner().to_string(),
+                    (i + 1).to_string(),
+                    optional_cell(part.time.as_deref()),
+                    optional_cell(part.nanos()),
+                    optional_cell(timing.max_rss),
+                    optional_cell(allocs.map(|a| a.allocations)),
+                    optional_cell(allocs.map(|a| a.bytes)),
+                    optional_cell(allocs.map(|a| a.peak_bytes)),
+                ]
+                .join(","),
+            );
+        }
+    }
+
+    lines.join("\n") + "\n"
+}
+
+fn optional_number(value: Option<f64>) -> JsonValue {
+    value.map_or(JsonValue::Null, JsonValue::Number)
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn to_json(year: Option<Year>, timings: &Timings) -> JsonValue {
+    let days = timings
+        .data
+        .iter()
+        .map(|timing| {
+            let parts = timing
+                .parts
+                .iter()
+                .enumerate()
+                .map(|(i, part)| {
+                    let mut map: HashMap<String, JsonValue> = HashMap::new();
+                    map.insert("part".into(), JsonValue::Number((i + 1) as f64));
+                    map.insert(
+                        "time".into(),
+                        part.time.clone().map_or(JsonValue::Null, JsonValue::String),
+                    );
+                    map.insert("nanos".into(), optional_number(part.nanos()));
+                    if let Some(allocs) = &part.allocs {
+                        map.insert("allocs".into(), JsonValue::from(allocs));
+                    }
+                    JsonValue::Object(map)
+                })
+                .collect();
+
+            let mut map: HashMap<String, JsonValue> = HashMap::new();
+            map.insert(
+                "day".into(),
+                JsonValue::Number(f64::from(timing.day.into_inner())),
+            );
+            map.insert("total_nanos".into(), JsonValue::Number(timing.total_nanos));
+            map.insert(
+                "max_rss_bytes".into(),
+                optional_number(timing.max_rss.map(|x| x as f64)),
+            );
+            map.insert("parts".into(), JsonValue::Array(parts));
+            JsonValue::Object(map)
+        })
+        .collect();
+
+    let mut map: HashMap<String, JsonValue> = HashMap::new();
+    map.insert(
+        "year".into(),
+        optional_number(year.map(|year| f64::from(year.into_inner()))),
+    );
+    map.insert(
+        "total_nanos".into(),
+        JsonValue::Number(timings.total_millis() * 1_000_000_f64),
+    );
+    map.insert(
+        "environment".into(),
+        timings
+            .environment
+            .as_ref()
+            .map_or(JsonValue::Null, JsonValue::from),
+    );
+    map.insert("days".into(), JsonValue::Array(days));
+    JsonValue::Object(map)
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{relative_root, render, Format};
+    use crate::template::{
+        alloc_stats::AllocStats,
+        timings::{fixtures::mock_timings, Timings},
+    };
+    use std::{collections::HashMap, env, path::Path, str::FromStr};
+    use tinyjson::JsonValue;
+
+    /// The shared fixture, with allocation counts and peak memory for the first day.
+    fn get_mock_timings() -> Timings {
+        let mut timings = mock_timings();
+        timings.data[0].parts[0].allocs = Some(AllocStats {
+            allocations: 3,
+            bytes: 128,
+            peak_bytes: 64,
+        });
+        timings.data[0].max_rss = Some(1024);
+        timings
+    }
+
+    #[test]
+    fn parses_formats() {
+        assert_eq!(Format::from_str("CSV"), Ok(Format::Csv));
+        assert_eq!(Format::from_str("markdown"), Ok(Format::Markdown));
+        assert_eq!(Format::from_str("md"), Ok(Format::Markdown));
+        assert_eq!(Format::from_str("xml").is_err(), true);
+    }
+
+    #[test]
+    fn exports_csv() {
+        let csv = render(Format::Csv, "..", None, &get_mock_timings()).unwrap();
+        assert_eq!(
+            csv,
+            "day,part,time,nanos,max_rss_bytes,allocations,allocated_bytes,peak_bytes
+1,1,74.13µs,74130,1024,3,128,64
+1,2,2.5ms,2500000,1024,,,
+2,1,1.2s,1200000000,,,,
+2,2,,,,,,
+"
+        );
+    }
+
+    #[test]
+    fn exports_json() {
+        let json = render(Format::Json, "..", None, &get_mock_timings()).unwrap();
+        let json: JsonValue = json.parse().unwrap();
+        let json: &HashMap<String, JsonValue> = json.get().unwrap();
+
+        assert_eq!(json["year"], JsonValue::Null);
+        assert_eq!(json["total_nanos"], JsonValue::Number(1_202_574_130.0));
+
+        let days: &Vec<JsonValue> = json["days"].get().unwrap();
+        let part: &HashMap<String, JsonValue> = days[0]["parts"][1].get().unwrap();
+        assert_eq!(part["time"], JsonValue::String("2.5ms".into()));
+        assert_eq!(part["nanos"], JsonValue::Number(2_500_000.0));
+        assert_eq!(days[1]["parts"][1]["nanos"], JsonValue::Null);
+        assert_eq!(
+            days[0]["parts"][0]["allocs"]["bytes"],
+            JsonValue::Number(128.0)
+        );
+    }
+
+    #[test]
+    fn exports_markdown() {
+        let md = render(Format::Markdown, "..", None, &get_mock_timings()).unwrap();
+        assert_eq!(md.starts_with("# Benchmarks\n"), true);
+        assert_eq!(
+            md.contains("| [Day 1](../src/bin/01.rs) | `74.13µs` | `2.5ms` |"),
+            true
+        );
+        assert_eq!(md.contains("**Total: 1202.57ms**"), true);
+    }
+
+    #[test]
+    fn links_relative_to_output() {
+        assert_eq!(relative_root(Path::new("benchmarks.md")), ".");
+        assert_eq!(relative_root(Path::new("./data/benchmarks.md")), "..");
+        assert_eq!(relative_root(Path::new("data/2023/benchmarks.md")), "../..");
+
+        let cwd = env::current_dir().unwrap();
+        assert_eq!(relative_root(&cwd.join("target/benchmarks.md")), "..");
+        assert_eq!(
+            relative_root(Path::new("../benchmarks.md")),
+            cwd.display().to_string()
+        );
+    }
+}
@@ -0,0 +1,154 @@
+/// Snapshots of the total benchmark time of a year, used to chart trends.
+/// A snapshot is recorded every time benchmarks are stored with `cargo time --store --history`.
+use std::{collections::HashMap, fs, io::Error, str::FromStr};
+use tinyjson::JsonValue;
+
+use crate::template::{paths, Year};
+
+/// Total benchmark time at one point in time, labeled e.g. with the commit it was measured at.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Snapshot {
+    pub label: String,
+    pub total_nanos: f64,
+}
+
+/// Snapshots are kept for a limited number of runs, so that the chart stays readable.
+const MAX_SNAPSHOTS: usize = 50;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct History {
+    pub data: Vec<Snapshot>,
+}
+
+impl History {
+    /// Rehydrate the history of a year. If not present, returns an empty history.
+    pub fn read_from_file(year: Option<Year>) -> Self {
+        fs::read_to_string(paths::history_path(year))
+            .map_err(|x| x.to_string())
+            .and_then(History::try_from)
+            .unwrap_or_default()
+    }
+
+    /// Dehydrate the history to the JSON file of a year.
+    pub fn store_file(&self, year: Option<Year>) -> Result<(), Error> {
+        let json = JsonValue::from(self);
+        let mut file = fs::File::create(paths::history_path(year))?;
+        json.format_to(&mut file)
+    }
+
+    /// Add a snapshot, dropping the oldest ones if there are too many.
+    pub fn push(&mut self, snapshot: Snapshot) {
+        self.data.push(snapshot);
+        if self.data.len() > MAX_SNAPSHOTS {
+            self.data.drain(..self.data.len() - MAX_SNAPSHOTS);
+        }
+    }
+}
+
+impl From<&History> for JsonValue {
+    fn from(value: &History) -> Self {
+        let data = value
+            .data
+            .iter()
+            .map(|snapshot| {
+                let mut map: HashMap<String, JsonValue> = HashMap::new();
+                map.insert("label".into(), JsonValue::String(snapshot.label.clone()));
+                map.insert(
+                    "total_nanos".into(),
+                    JsonValue::Number(snapshot.total_nanos),
+                );
+                JsonValue::Object(map)
+            })
+            .collect();
+
+        let mut map: HashMap<String, JsonValue> = HashMap::new();
+        map.insert("data".into(), JsonValue::Array(data));
+        JsonValue::Object(map)
+    }
+}
+
+impl TryFrom<&JsonValue> for Snapshot {
+    type Error = String;
+
+    fn try_from(value: &JsonValue) -> Result<Self, Self::Error> {
+        let json = value
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("Expected snapshot to be a JSON object.")?;
+
+        Ok(Snapshot {
+            label: json
+                .get("label")
+                .and_then(|v| v.get::<String>())
+                .ok_or("Expected snapshot.label to be a string.")?
+                .clone(),
+            total_nanos: *json
+                .get("total_nanos")
+                .and_then(|v| v.get::<f64>())
+                .ok_or("Expected snapshot.total_nanos to be a number.")?,
+        })
+    }
+}
+
+impl TryFrom<String> for History {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let json = JsonValue::from_str(&value).or(Err("not valid JSON file."))?;
+
+        let data = json
+            .get::<HashMap<String, JsonValue>>()
+            .ok_or("expected JSON document to be an object.")?
+            .get("data")
+            .ok_or("expected JSON document to have key `data`.")?
+            .get::<Vec<JsonValue>>()
+            .ok_or("expected `json.data` to be an array.")?;
+
+        Ok(History {
+            data: data
+                .iter()
+                .map(Snapshot::try_from)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{History, Snapshot, MAX_SNAPSHOTS};
+    use tinyjson::JsonValue;
+
+    fn snapshot(n: usize) -> Snapshot {
+        Snapshot {
+            label: format!("#{n}"),
+            total_nanos: n as f64,
+        }
+    }
+
+    #[test]
+    fn serializes_history() {
+        let mut history = History::default();
+        history.push(snapshot(1));
+        history.push(snapshot(2));
+
+        let json = JsonValue::from(&history).stringify().unwrap();
+        assert_eq!(History::try_from(json).unwrap(), history);
+    }
+
+    #[test]
+    fn drops_oldest_snapshots() {
+        let mut history = History::default();
+        for n in 0..MAX_SNAPSHOTS + 2 {
+            history.push(snapshot(n));
+        }
+        assert_eq!(history.data.len(), MAX_SNAPSHOTS);
+        assert_eq!(history.data[0], snapshot(2));
+    }
+
+    #[test]
+    fn rejects_malformed_history() {
+        let json = r#"{ "data": [{ "label": "abc" }] }"#.to_string();
+        assert_eq!(History::try_from(json).is_err(), true);
+    }
+}
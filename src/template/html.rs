@@ -0,0 +1,382 @@
+//! Renders puzzle descriptions and solution sources to HTML for the report.
+
+use crate::template::aoc_client::DEFAULT_BASE_URL;
+
+/// Escape text for HTML content and attribute values.
+pub fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Links in puzzle descriptions are relative to the puzzle page, e.g. `/2024/day/1/input`.
+fn resolve_href(href: &str) -> String {
+    if href.starts_with('/') {
+        format!("{DEFAULT_BASE_URL}{href}")
+    } else {
+        href.into()
+    }
+}
+
+/// Convert the inline elements of a line: `code`, *emphasis* and [links](href).
+fn render_inline(line: &str) -> String {
+    let mut out = String::new();
+    let mut rest = line;
+
+    while let Some(start) = rest.find(['`', '*', '[', '\\']) {
+        out.push_str(&escape(&rest[..start]));
+        let tail = &rest[start..];
+
+        let html = match tail.as_bytes()[0] {
+            b'`' => tail[1..].find('`').map(|len| {
+                let code = &tail[1..=len];
+                let html = match code.strip_prefix('*').and_then(|c| c.strip_suffix('*')) {
+                    Some(em) => format!("<code><em>{}</em></code>", escape(em)),
+                    None => format!("<code>{}</code>", escape(code)),
+                };
+                (html, len + 2)
+            }),
+            b'*' => tail[1..].find('*').filter(|len| *len > 0).map(|len| {
+                (
+                    format!("<em>{}</em>", render_inline(&tail[1..=len])),
+                    len + 2,
+                )
+            }),
+            b'\\' => tail[1..]
+                .chars()
+                .next()
+                .filter(char::is_ascii_punctuation)
+                .map(|c| (escape(&c.to_string()), 2)),
+            _ => tail
+                .find(']')
+                .filter(|mid| tail[mid + 1..].starts_with('('))
+                .and_then(|mid| {
+                    let len = tail[mid..].find(')')? + mid;
+                    let href = resolve_href(&tail[mid + 2..len]);
+                    Some((
+                        format!(
+                            r#"<a href="{}">{}</a>"#,
+                            escape(&href),
+                            render_inline(&tail[1..mid])
+                        ),
+                        len + 1,
+                    ))
+                }),
+        };
+
+        match html {
+            Some((html, len)) => {
+                out.push_str(&html);
+                rest = &tail[len..];
+            }
+            None => {
+                out.push_str(&escape(&tail[..1]));
+                rest = &tail[1..];
+            }
+        }
+    }
+
+    out.push_str(&escape(rest));
+    out
+}
+
+/// Convert a puzzle description to HTML.
+pub fn markdown_to_html(markdown: &str) -> String {
+    let mut out: Vec<String> = vec![];
+    let mut paragraph: Vec<&str> = vec![];
+    let mut in_list = false;
+    let mut in_code = false;
+
+    let flush = |out: &mut Vec<String>, paragraph: &mut Vec<&str>, in_list: &mut bool| {
+        if !paragraph.is_empty() {
+            out.push(format!("<p>{}</p>", render_inline(&paragraph.join(" "))));
+            paragraph.clear();
+        }
+        if *in_list {
+            out.push("</ul>".into());
+            *in_list = false;
+        }
+    };
+
+    for line in markdown.lines() {
+        if line.starts_with("```") {
+            if in_code {
+                out.push("</code></pre>".into());
+            } else {
+                flush(&mut out, &mut paragraph, &mut in_list);
+                out.push("<pre><code>".into());
+            }
+            in_code = !in_code;
+            continue;
+        }
+
+        if in_code {
+            // the newline is added when joining, so the opening tag must not add one.
+            let last = out.last_mut().unwrap();
+            if last.ends_with("<pre><code>") {
+                last.push_str(&escape(line));
+            } else {
+                out.push(escape(line));
+            }
+            continue;
+        }
+
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            flush(&mut out, &mut paragraph, &mut in_list);
+        } else if let Some(heading) = line.strip_prefix("## ") {
+            flush(&mut out, &mut paragraph, &mut in_list);
+            out.push(format!("<h2>{}</h2>", render_inline(heading)));
+        } else if trimmed.len() >= 3 && trimmed.chars().all(|c| c == '-') && !paragraph.is_empty() {
+            // aoc-cli underlines headings, e.g. `\--- Day 1: Historian Hysteria ---` and `----------`.
+            let heading = paragraph.join(" ");
+            paragraph.clear();
+            flush(&mut out, &mut paragraph, &mut in_list);
+            out.push(format!("<h2>{}</h2>", render_inline(&heading)));
+        } else if let Some(item) = line.strip_prefix("- ") {
+            if !paragraph.is_empty() {
+                flush(&mut out, &mut paragraph, &mut in_list);
+            }
+            if !in_list {
+                out.push("<ul>".into());
+                in_list = true;
+            }
+            out.push(format!("<li>{}</li>", render_inline(item)));
+        } else {
+            if in_list {
+                out.push("</ul>".into());
+                in_list = false;
+            }
+            paragraph.push(trimmed);
+        }
+    }
+
+    if in_code {
+        out.push("</code></pre>".into());
+    }
+    flush(&mut out, &mut paragraph, &mut in_list);
+
+    out.join("\n")
+}
+
+const KEYWORDS: [&str; 38] = [
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true", "type",
+    "unsafe", "use", "where", "while",
+];
+
+/// Length of a string literal that starts at `chars[0]`, i.e. `"..."` or a raw string like `r#"..."#`.
+fn string_len(chars: &[char]) -> Option<usize> {
+    let (hashes, quote) = match chars[0] {
+        '"' => (0, 0),
+        'r' => {
+            let hashes = chars[1..].iter().take_while(|c| **c == '#').count();
+            (hashes, 1 + hashes)
+        }
+        _ => return None,
+    };
+
+    if chars.get(quote) != Some(&'"') {
+        return None;
+    }
+
+    let raw = chars[0] == 'r';
+    let mut i = quote + 1;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' if !raw => i += 2,
+            '"' if chars[i + 1..]
+                .iter()
+                .take(hashes)
+                .filter(|c| **c == '#')
+                .count()
+                == hashes =>
+            {
+                return Some(i + 1 + hashes)
+            }
+            _ => i += 1,
+        }
+    }
+    Some(chars.len())
+}
+
+/// Length of a char literal like `'a'` or `'\n'`. Lifetimes like `'a` are not char literals.
+fn char_len(chars: &[char]) -> Option<usize> {
+    match chars.get(1)? {
+        // the escaped char is skipped, so that `'\''` is read as a whole.
+        '\\' => Some(chars.get(3..)?.iter().position(|c| *c == '\'')? + 4),
+        _ if chars.get(2) == Some(&'\'') => Some(3),
+        _ => None,
+    }
+}
+
+fn is_ident(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Highlight rust source code with `<span>`s of the classes
+/// `comment`, `string`, `number`, `keyword`, `macro`, `attribute`, `lifetime` and `type`.
+pub fn highlight_rust(source: &str) -> String {
+    let chars: Vec<char> = source.chars().collect();
+    let mut out = String::new();
+    let mut i = 0;
+
+    let starts_with = |i: usize, s: &str| {
+        s.chars()
+            .enumerate()
+            .all(|(n, c)| chars.get(i + n) == Some(&c))
+    };
+
+    while i < chars.len() {
+        let c = chars[i];
+        let rest = &chars[i..];
+
+        let token: Option<(&str, usize)> = if starts_with(i, "//") {
+            Some((
+                "comment",
+                rest.iter().position(|c| *c == '\n').unwrap_or(rest.len()),
+            ))
+        } else if starts_with(i, "/*") {
+            let len = (2..rest.len())
+                .find(|n| rest[*n - 1] == '*' && rest[*n] == '/')
+                .map_or(rest.len(), |n| n + 1);
+            Some(("comment", len))
+        } else if starts_with(i, "#[") || starts_with(i, "#![") {
+            let mut depth = 0;
+            let len = rest
+                .iter()
+                .position(|c| {
+                    match c {
+                        '[' => depth += 1,
+                        ']' => depth -= 1,
+                        _ => {}
+                    }
+                    *c == ']' && depth == 0
+                })
+                .map_or(rest.len(), |n| n + 1);
+            Some(("attribute", len))
+        } else if c == '"' || (c == 'r' && matches!(rest.get(1), Some('"' | '#'))) {
+            string_len(rest).map(|len| ("string", len))
+        } else if c == '\'' {
+            char_len(rest).map(|len| ("string", len)).or_else(|| {
+                let len = rest[1..].iter().take_while(|c| is_ident(**c)).count();
+                (len > 0).then_some(("lifetime", len + 1))
+            })
+        } else if c.is_ascii_digit() && (i == 0 || !is_ident(chars[i - 1])) {
+            let mut len = 0;
+            while len < rest.len()
+                && (is_ident(rest[len])
+                    || (rest[len] == '.' && rest.get(len + 1).is_some_and(char::is_ascii_digit)))
+            {
+                len += 1;
+            }
+            Some(("number", len))
+        } else if is_ident(c) && (i == 0 || !is_ident(chars[i - 1])) {
+            let len = rest.iter().take_while(|c| is_ident(**c)).count();
+            let word: String = rest[..len].iter().collect();
+            if rest.get(len) == Some(&'!') && rest.get(len + 1) != Some(&'=') {
+                Some(("macro", len + 1))
+            } else if KEYWORDS.contains(&word.as_str()) {
+                Some(("keyword", len))
+            } else if c.is_uppercase() {
+                Some(("type", len))
+            } else {
+                out.push_str(&word);
+                i += len;
+                continue;
+            }
+        } else {
+            None
+        };
+
+        match token {
+            Some((class, len)) => {
+                let text: String = rest[..len].iter().collect();
+                out.push_str(&format!(
+                    r#"<span class="{class}">{}</span>"#,
+                    escape(&text)
+                ));
+                i += len;
+            }
+            None => {
+                out.push_str(&escape(&c.to_string()));
+                i += 1;
+            }
+        }
+    }
+
+    out
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{highlight_rust, markdown_to_html};
+
+    #[test]
+    fn converts_markdown() {
+        let markdown = "## --- Day 1: Historian Hysteria ---
+
+The *Chief Historian* is always present.
+Check the [list](/2024/day/1/input).
+
+```
+3   4
+4 < 3
+```
+
+- Item with `code`
+- The answer is `*11*`
+";
+        assert_eq!(
+            markdown_to_html(markdown),
+            r#"<h2>--- Day 1: Historian Hysteria ---</h2>
+<p>The <em>Chief Historian</em> is always present. Check the <a href="https://adventofcode.com/2024/day/1/input">list</a>.</p>
+<pre><code>3   4
+4 &lt; 3
+</code></pre>
+<ul>
+<li>Item with <code>code</code></li>
+<li>The answer is <code><em>11</em></code></li>
+</ul>"#
+        );
+    }
+
+    #[test]
+    fn converts_underlined_headings() {
+        assert_eq!(
+            markdown_to_html("\\--- Day 1: Historian Hysteria ---\n----------\n\nText"),
+            "<h2>--- Day 1: Historian Hysteria ---</h2>\n<p>Text</p>"
+        );
+    }
+
+    #[test]
+    fn converts_brackets_before_links() {
+        assert_eq!(
+            markdown_to_html("Add [1,2] then [see](/x)."),
+            r#"<p>Add [1,2] then <a href="https://adventofcode.com/x">see</a>.</p>"#
+        );
+    }
+
+    #[test]
+    fn highlights_rust() {
+        assert_eq!(
+            highlight_rust(r#"let x: Vec<u32> = vec![1, 2]; // "note""#),
+            concat!(
+                r#"<span class="keyword">let</span> x: <span class="type">Vec</span>&lt;u32&gt; = "#,
+                r#"<span class="macro">vec!</span>[<span class="number">1</span>, <span class="number">2</span>]; "#,
+                r#"<span class="comment">// &quot;note&quot;</span>"#
+            )
+        );
+        assert_eq!(
+            highlight_rust(r##"#[test] fn f<'a>(c: char) { r#"x"#; '\n'; }"##),
+            concat!(
+                r#"<span class="attribute">#[test]</span> <span class="keyword">fn</span> f&lt;<span class="lifetime">'a</span>&gt;(c: char) { "#,
+                r##"<span class="string">r#&quot;x&quot;#</span>; <span class="string">'\n'</span>; }"##
+            )
+        );
+    }
+}
@@ -1,15 +1,34 @@
 use std::{env, fs};
 
+pub mod alloc_stats;
 pub mod aoc_cli;
+pub mod aoc_client;
+pub mod backend;
 pub mod commands;
+pub mod export;
 pub mod runner;
 
 pub use day::*;
+pub use year::*;
 
+mod budgets;
+mod chart;
 mod day;
+mod environment;
+mod history;
+mod html;
+mod paths;
+mod puzzle_html;
+mod puzzle_info;
+mod puzzle_render;
+mod readme;
 mod readme_benchmarks;
+mod readme_stars;
+mod report;
 mod run_multi;
+mod rusage;
 mod timings;
+mod year;
 
 pub const ANSI_ITALIC: &str = "\x1b[3m";
 pub const ANSI_BOLD: &str = "\x1b[1m";
@@ -18,18 +37,34 @@ pub const ANSI_RESET: &str = "\x1b[0m";
 /// Helper function that reads a text file to a string.
 #[must_use]
 pub fn read_file(folder: &str, day: Day) -> String {
+    read_year_file(folder, None, day)
+}
+
+/// Helper function that reads a text file to string, appending a part suffix. E.g. like `01-2.txt`.
+#[must_use]
+pub fn read_file_part(folder: &str, day: Day, part: u8) -> String {
+    read_year_file_part(folder, None, day, part)
+}
+
+/// Helper function that reads a text file of a year to a string.
+/// Without a year, this is the same as [`read_file`].
+#[must_use]
+pub fn read_year_file(folder: &str, year: Option<Year>, day: Day) -> String {
     let cwd = env::current_dir().unwrap();
-    let filepath = cwd.join("data").join(folder).join(format!("{day}.txt"));
+    let filepath = cwd
+        .join(paths::data_dir(year))
+        .join(folder)
+        .join(format!("{day}.txt"));
     let f = fs::read_to_string(filepath);
     f.expect("could not open input file")
 }
 
-/// Helper function that reads a text file to string, appending a part suffix. E.g. like `01-2.txt`.
+/// Helper function that reads a text file of a year to string, appending a part suffix. E.g. like `01-2.txt`.
 #[must_use]
-pub fn read_file_part(folder: &str, day: Day, part: u8) -> String {
+pub fn read_year_file_part(folder: &str, year: Option<Year>, day: Day, part: u8) -> String {
     let cwd = env::current_dir().unwrap();
     let filepath = cwd
-        .join("data")
+        .join(paths::data_dir(year))
         .join(folder)
         .join(format!("{day}-{part}.txt"));
     let f = fs::read_to_string(filepath);
@@ -37,8 +72,11 @@ pub fn read_file_part(folder: &str, day: Day, part: u8) -> String {
 }
 
 /// Creates the constant `DAY` and sets up the input and runner for each part.
+/// When run, the day is checked against the calendar of its year, or the year configured in `AOC_YEAR`.
 ///
 /// The optional, second parameter (1 or 2) allows you to only run a single part of the solution.
+/// Alternatively, a list of part functions can be passed for days that do not have exactly two parts,
+/// e.g. `solution!(1, [part_one, part_two, part_three])`. Parts are numbered in the order they are listed.
 #[macro_export]
 macro_rules! solution {
     ($day:expr) => {
@@ -50,19 +88,39 @@ macro_rules! solution {
     ($day:expr, 2) => {
         $crate::solution!(@impl $day, [part_two, 2]);
     };
+    ($day:expr, [$($func:ident),+ $(,)?]) => {
+        $crate::solution!(@parts $day, [] 1, $($func),+);
+    };
+
+    (@parts $day:expr, [$($acc:tt)*] $part:expr, $func:ident $(, $rest:ident)*) => {
+        $crate::solution!(@parts $day, [$($acc)* [$func, $part]] $part + 1 $(, $rest)*);
+    };
+    (@parts $day:expr, [$($acc:tt)*] $part:expr) => {
+        $crate::solution!(@impl $day, $($acc)*);
+    };
 
     (@impl $day:expr, $( [$func:expr, $part:expr] )*) => {
         /// The current day.
         const DAY: $crate::template::Day = $crate::day!($day);
 
+        /// The year of the current day, if the solution lives in a per-year directory.
+        const YEAR: Option<$crate::template::Year> = $crate::template::Year::from_bin_path(file!());
+
         #[cfg(feature = "dhat-heap")]
         #[global_allocator]
         static ALLOC: dhat::Alloc = dhat::Alloc;
 
+        #[cfg(feature = "alloc-stats")]
+        #[global_allocator]
+        static ALLOC: $crate::template::alloc_stats::CountingAlloc =
+            $crate::template::alloc_stats::CountingAlloc;
+
         fn main() {
             use $crate::template::runner::*;
-            let input = $crate::template::read_file("inputs", DAY);
-            $( run_part($func, &input, DAY, $part); )*
+            configure_thread_pool();
+            check_calendar(YEAR, DAY);
+            let input = $crate::template::read_year_file("inputs", YEAR, DAY);
+            $( run_part($func, &input, YEAR, DAY, $part); )*
         }
     };
 }
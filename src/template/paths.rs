@@ -0,0 +1,98 @@
+/// Locations of the files that belong to a day.
+/// Without a year, solutions and data live in `src/bin` and `data` directly.
+/// With a year, every year gets its own directory, e.g. `src/bin/2023/01.rs` and `data/2023/inputs/01.txt`.
+use crate::template::{Day, Year};
+
+/// Name of the cargo bin target of a solution, e.g. `01` or `2023-01`.
+pub fn bin_name(year: Option<Year>, day: Day) -> String {
+    match year {
+        Some(year) => format!("{year}-{day}"),
+        None => day.to_string(),
+    }
+}
+
+pub fn bin_path(year: Option<Year>, day: Day) -> String {
+    match year {
+        Some(year) => format!("src/bin/{year}/{day}.rs"),
+        None => format!("src/bin/{day}.rs"),
+    }
+}
+
+pub fn data_dir(year: Option<Year>) -> String {
+    match year {
+        Some(year) => format!("data/{year}"),
+        None => "data".into(),
+    }
+}
+
+pub fn input_path(year: Option<Year>, day: Day) -> String {
+    format!("{}/inputs/{day}.txt", data_dir(year))
+}
+
+pub fn example_path(year: Option<Year>, day: Day) -> String {
+    format!("{}/examples/{day}.txt", data_dir(year))
+}
+
+pub fn puzzle_path(year: Option<Year>, day: Day) -> String {
+    format!("{}/puzzles/{day}.md", data_dir(year))
+}
+
+pub fn timings_path(year: Option<Year>) -> String {
+    format!("{}/timings.json", data_dir(year))
+}
+
+pub fn history_path(year: Option<Year>) -> String {
+    format!("{}/timings_history.json", data_dir(year))
+}
+
+pub fn budgets_path(year: Option<Year>) -> String {
+    format!("{}/budgets.json", data_dir(year))
+}
+
+/// Default destination of `cargo time --export`, e.g. `data/benchmarks.csv`.
+pub fn export_path(year: Option<Year>, extension: &str) -> String {
+    format!("{}/benchmarks.{extension}", data_dir(year))
+}
+
+/// Default destination of `cargo report`, next to the docs that `cargo doc` writes to `target`.
+pub fn report_dir(year: Option<Year>) -> String {
+    match year {
+        Some(year) => format!("target/report/{year}"),
+        None => "target/report".into(),
+    }
+}
+
+/// The benchmark chart that is linked from the readme, e.g. `.assets/benchmarks-2023.svg`.
+pub fn chart_path(year: Option<Year>) -> String {
+    match year {
+        Some(year) => format!(".assets/benchmarks-{year}.svg"),
+        None => ".assets/benchmarks.svg".into(),
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{bin_name, bin_path, chart_path, input_path, timings_path};
+    use crate::{day, template::Year};
+
+    #[test]
+    fn resolves_flat_layout() {
+        assert_eq!(bin_name(None, day!(1)), "01");
+        assert_eq!(bin_path(None, day!(1)), "src/bin/01.rs");
+        assert_eq!(input_path(None, day!(1)), "data/inputs/01.txt");
+        assert_eq!(timings_path(None), "data/timings.json");
+        assert_eq!(chart_path(None), ".assets/benchmarks.svg");
+    }
+
+    #[test]
+    fn resolves_year_layout() {
+        let year = Year::new(2023);
+        assert_eq!(bin_name(year, day!(1)), "2023-01");
+        assert_eq!(bin_path(year, day!(1)), "src/bin/2023/01.rs");
+        assert_eq!(input_path(year, day!(1)), "data/2023/inputs/01.txt");
+        assert_eq!(timings_path(year), "data/2023/timings.json");
+        assert_eq!(chart_path(year), ".assets/benchmarks-2023.svg");
+    }
+}
@@ -0,0 +1,270 @@
+//! Converts the HTML of a puzzle page to markdown.
+//! Only handles the small subset of HTML that is used in puzzle descriptions.
+
+enum Token<'a> {
+    Text(&'a str),
+    Open(&'a str, &'a str),
+    Close(&'a str),
+}
+
+fn tokenize(html: &str) -> Vec<Token<'_>> {
+    let mut tokens = vec![];
+    let mut rest = html;
+
+    while !rest.is_empty() {
+        let Some(start) = rest.find('<') else {
+            tokens.push(Token::Text(rest));
+            break;
+        };
+
+        if start > 0 {
+            tokens.push(Token::Text(&rest[..start]));
+        }
+
+        let Some(end) = rest[start..].find('>') else {
+            tokens.push(Token::Text(&rest[start..]));
+            break;
+        };
+
+        let tag = rest[start + 1..start + end].trim_end_matches('/').trim();
+        if let Some(name) = tag.strip_prefix('/') {
+            tokens.push(Token::Close(name.trim()));
+        } else if !tag.starts_with('!') {
+            let (name, attrs) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+            tokens.push(Token::Open(name, attrs));
+        }
+
+        rest = &rest[start + end + 1..];
+    }
+
+    tokens
+}
+
+/// Decode the HTML entities that appear in puzzle descriptions.
+pub fn decode_entities(s: &str) -> String {
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
+
+fn get_attribute<'a>(attrs: &'a str, name: &str) -> Option<&'a str> {
+    let start = attrs.find(&format!("{name}=\""))? + name.len() + 2;
+    let len = attrs[start..].find('"')?;
+    Some(&attrs[start..start + len])
+}
+
+/// Convert a fragment of puzzle HTML to markdown.
+pub fn to_markdown(html: &str) -> String {
+    let mut out = String::new();
+    let mut in_pre = false;
+    let mut links: Vec<String> = vec![];
+
+    for token in tokenize(html) {
+        match token {
+            Token::Text(text) if in_pre => out.push_str(&decode_entities(text)),
+            Token::Text(text) => {
+                if text.trim().is_empty() && (out.is_empty() || out.ends_with('\n')) {
+                    continue;
+                }
+                out.push_str(&decode_entities(&text.replace('\n', " ")));
+            }
+            Token::Open(name, attrs) => match name {
+                "h2" => out.push_str("## "),
+                "pre" => {
+                    out.push_str("```\n");
+                    in_pre = true;
+                }
+                "code" if !in_pre => out.push('`'),
+                "em" if !in_pre => out.push('*'),
+                "li" => out.push_str("- "),
+                "br" => out.push('\n'),
+                "a" => {
+                    links.push(get_attribute(attrs, "href").unwrap_or_default().into());
+                    out.push('[');
+                }
+                _ => {}
+            },
+            Token::Close(name) => match name {
+                "h2" | "p" | "ul" => {
+                    out.truncate(out.trim_end_matches(' ').len());
+                    out.push_str("\n\n");
+                }
+                "pre" => {
+                    if !out.ends_with('\n') {
+                        out.push('\n');
+                    }
+                    out.push_str("```\n\n");
+                    in_pre = false;
+                }
+                "code" if !in_pre => out.push('`'),
+                "em" if !in_pre => out.push('*'),
+                "li" => out.push('\n'),
+                "a" => {
+                    let href = links.pop().unwrap_or_default();
+                    out.push_str(&format!("]({href})"));
+                }
+                _ => {}
+            },
+        }
+    }
+
+    let mut markdown = out.trim().replace("\n\n\n", "\n\n");
+    markdown.push('\n');
+    markdown
+}
+
+/// Extract the puzzle description from a puzzle page as markdown.
+/// This includes the description of each unlocked part and the answers that were already given.
+pub fn extract_description(html: &str) -> String {
+    let mut sections = vec![];
+    let mut rest = html;
+
+    loop {
+        let article = rest.find("<article").map(|i| (i, "</article>"));
+        let answer = rest.find("<p>Your puzzle answer was").map(|i| (i, "</p>"));
+
+        let next = match (article, answer) {
+            (Some(a), Some(b)) => Some(if a.0 < b.0 { a } else { b }),
+            (a, b) => a.or(b),
+        };
+
+        let Some((start, end_tag)) = next else {
+            break;
+        };
+
+        let Some(len) = rest[start..].find(end_tag) else {
+            break;
+        };
+
+        let end = start + len + end_tag.len();
+        sections.push(to_markdown(&rest[start..end]));
+        rest = &rest[end..];
+    }
+
+    sections.join("\n")
+}
+
+/// Extract the plain text of the first `<article>` of a page, e.g. the verdict of a submission.
+pub fn extract_article_text(html: &str) -> Option<String> {
+    let start = html.find("<article")?;
+    let end = html[start..].find("</article>")? + start;
+
+    let text = tokenize(&html[start..end])
+        .into_iter()
+        .filter_map(|token| match token {
+            Token::Text(text) => Some(decode_entities(text)),
+            _ => None,
+        })
+        .collect::<String>();
+
+    Some(text.split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
+/// Return the lines that were added to a puzzle description, e.g. once part two was unlocked.
+pub fn revealed_lines<'a>(old: &str, new: &'a str) -> Vec<&'a str> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    new[prefix..new.len() - suffix].to_vec()
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{extract_article_text, extract_description, revealed_lines, to_markdown};
+
+    const PUZZLE: &str = r#"<main>
+<article class="day-desc"><h2>--- Day 1: Historian Hysteria ---</h2><p>The <em>Chief Historian</em> is always present.</p>
+<p>For example:</p>
+<pre><code>3   4
+4   3
+</code></pre>
+<ul>
+<li>The first pair is <code>3 &lt; 4</code>.</li>
+<li>See <a href="/2024/about" target="_blank">about</a>.</li>
+</ul>
+</article>
+<p>Your puzzle answer was <code>1234</code>.</p><p class="day-success">The first half of this puzzle is complete!</p>
+<form method="post"></form>
+</main>"#;
+
+    #[test]
+    fn converts_puzzle_to_markdown() {
+        let expected = [
+            "## --- Day 1: Historian Hysteria ---",
+            "",
+            "The *Chief Historian* is always present.",
+            "",
+            "For example:",
+            "",
+            "```",
+            "3   4",
+            "4   3",
+            "```",
+            "",
+            "- The first pair is `3 < 4`.",
+            "- See [about](/2024/about).",
+            "",
+            "Your puzzle answer was `1234`.",
+            "",
+        ]
+        .join("\n");
+        assert_eq!(extract_description(PUZZLE), expected);
+    }
+
+    #[test]
+    fn converts_inline_elements() {
+        assert_eq!(
+            to_markdown("<p>a <code>b</code> <em>c</em></p>"),
+            "a `b` *c*\n"
+        );
+    }
+
+    #[test]
+    fn keeps_highlights_out_of_code_blocks() {
+        assert_eq!(
+            to_markdown("<pre><code>1 <em>2</em> 3\n</code></pre>"),
+            "```\n1 2 3\n```\n"
+        );
+    }
+
+    #[test]
+    fn extracts_article_text() {
+        let html = "<main><article><p>That's the right answer!  You are <span class=\"x\">one gold star</span> closer.</p></article></main>";
+        assert_eq!(
+            extract_article_text(html),
+            Some("That's the right answer! You are one gold star closer.".into())
+        );
+        assert_eq!(extract_article_text("<main></main>"), None);
+    }
+
+    #[test]
+    fn finds_revealed_lines() {
+        let old = "## Part 1\n\nFoo.\n";
+        let new = "## Part 1\n\nFoo.\n\nYour puzzle answer was `1`.\n\n## Part 2\n\nBar.\n";
+        assert_eq!(
+            revealed_lines(old, new),
+            vec![
+                "",
+                "Your puzzle answer was `1`.",
+                "",
+                "## Part 2",
+                "",
+                "Bar."
+            ]
+        );
+        assert_eq!(revealed_lines(new, new).is_empty(), true);
+    }
+}
@@ -0,0 +1,111 @@
+//! Extracts details like the title and the example answers from a cached puzzle description.
+//! Headings are recognized both as `## --- Day 1 ---` and in the escaped `\--- Day 1 ---` form of aoc-cli.
+
+/// Strip the decoration of a puzzle heading, e.g. `## --- Day 1: Historian Hysteria ---`.
+fn heading_text(line: &str) -> &str {
+    line.trim_start_matches(['#', '\\', ' '])
+        .trim_matches(['-', ' '])
+}
+
+/// The title of a puzzle, e.g. `Day 1: Historian Hysteria`.
+pub fn title(markdown: &str) -> Option<String> {
+    markdown
+        .lines()
+        .map(heading_text)
+        .find(|line| line.starts_with("Day "))
+        .map(String::from)
+}
+
+/// Split a puzzle description into the descriptions of its parts.
+fn parts(markdown: &str) -> Vec<String> {
+    let mut parts: Vec<String> = vec![];
+
+    for line in markdown.lines() {
+        let heading = heading_text(line);
+        if heading.starts_with("Day ") || heading.starts_with("Part ") {
+            parts.push(String::new());
+        }
+        if let Some(part) = parts.last_mut() {
+            part.push_str(line);
+            part.push('\n');
+        }
+    }
+
+    parts
+}
+
+/// The answer for the example of a part (1-based), if the description contains one.
+/// Puzzles highlight the example answer as `*answer*` in code, usually right before the question.
+pub fn example_answer(markdown: &str, part: usize) -> Option<String> {
+    let description = parts(markdown).into_iter().nth(part.checked_sub(1)?)?;
+
+    description
+        .lines()
+        .filter(|line| !line.starts_with("Your puzzle answer was"))
+        .flat_map(|line| line.split("`*").skip(1).filter_map(|s| s.split_once("*`")))
+        .map(|(answer, _)| answer.to_string())
+        .last()
+}
+
+/// The answers that were accepted for a puzzle, in order of the parts.
+pub fn answers(markdown: &str) -> Vec<String> {
+    markdown
+        .lines()
+        .filter_map(|line| line.strip_prefix("Your puzzle answer was `"))
+        .filter_map(|rest| rest.split_once('`'))
+        .map(|(answer, _)| answer.to_string())
+        .collect()
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{answers, example_answer, title};
+
+    const PUZZLE: &str = "## --- Day 1: Historian Hysteria ---
+
+For example:
+
+```
+3   4
+```
+
+In the example above, this is `2 + 1 = *3*`. The total is `*11*`.
+
+What is the total distance?
+
+Your puzzle answer was `1234`.
+
+## --- Part Two ---
+
+In the example above, the similarity score is `*31*`.
+";
+
+    #[test]
+    fn extracts_title() {
+        assert_eq!(title(PUZZLE), Some("Day 1: Historian Hysteria".into()));
+        assert_eq!(
+            title("\\--- Day 2: Red-Nosed Reports ---\n----------\n"),
+            Some("Day 2: Red-Nosed Reports".into())
+        );
+        assert_eq!(title(""), None);
+    }
+
+    #[test]
+    fn extracts_example_answers() {
+        assert_eq!(example_answer(PUZZLE, 1), Some("11".into()));
+        assert_eq!(example_answer(PUZZLE, 2), Some("31".into()));
+        assert_eq!(example_answer(PUZZLE, 3), None);
+        assert_eq!(example_answer(PUZZLE, 0), None);
+    }
+
+    #[test]
+    fn extracts_answers() {
+        assert_eq!(answers(PUZZLE), vec!["1234".to_string()]);
+        assert_eq!(
+            answers("## --- Day 2: Red-Nosed Reports ---\n"),
+            Vec::<String>::new()
+        );
+    }
+}
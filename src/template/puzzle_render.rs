@@ -0,0 +1,172 @@
+//! Renders the markdown of a puzzle description for the terminal.
+
+use std::{
+    env,
+    io::{self, IsTerminal, Write},
+    process::{Command, Stdio},
+};
+
+use colored::{ColoredString, Colorize};
+
+/// Apply a style to text, or leave it plain if colors are disabled.
+fn paint(text: &str, color: bool, style: impl Fn(&str) -> ColoredString) -> String {
+    if color {
+        style(text).to_string()
+    } else {
+        text.to_string()
+    }
+}
+
+/// Position of the `]` that closes the label of a link at the start of `tail`, if it is followed by `(`.
+fn link_label_end(tail: &str) -> Option<usize> {
+    tail.find(']')
+        .filter(|mid| tail[mid + 1..].starts_with('('))
+}
+
+/// Style the inline elements of a line: `code`, *emphasis* and [links](href).
+fn render_inline(line: &str, color: bool) -> String {
+    let mut out = String::new();
+    let mut rest = line;
+
+    while let Some(start) = rest.find(['`', '*', '[']) {
+        out.push_str(&rest[..start]);
+        let tail = &rest[start..];
+
+        let styled = match tail.as_bytes()[0] {
+            b'`' => tail[1..].find('`').map(|len| {
+                let code = &tail[1..=len];
+                let styled = match code.strip_prefix('*').and_then(|c| c.strip_suffix('*')) {
+                    Some(em) => paint(em, color, |s| s.cyan().bold()),
+                    None => paint(code, color, |s| s.cyan()),
+                };
+                (styled, len + 2)
+            }),
+            b'*' => tail[1..].find('*').filter(|len| *len > 0).map(|len| {
+                let em = tail[1..=len].replace('`', "");
+                (paint(&em, color, |s| s.bright_white().bold()), len + 2)
+            }),
+            _ => link_label_end(tail).and_then(|mid| {
+                let len = tail[mid..].find(')')? + mid;
+                Some((paint(&tail[1..mid], color, |s| s.underline()), len + 1))
+            }),
+        };
+
+        match styled {
+            Some((styled, len)) => {
+                out.push_str(&styled);
+                rest = &tail[len..];
+            }
+            None => {
+                out.push_str(&tail[..1]);
+                rest = &tail[1..];
+            }
+        }
+    }
+
+    out.push_str(rest);
+    out
+}
+
+/// Render a puzzle description to text, styled if `color` is set.
+pub fn render(markdown: &str, color: bool) -> String {
+    let mut lines = vec![];
+    let mut in_code = false;
+
+    for line in markdown.lines() {
+        if line.starts_with("```") {
+            in_code = !in_code;
+            continue;
+        }
+
+        if in_code {
+            lines.push(format!("    {}", paint(line, color, |s| s.cyan())));
+        } else if let Some(heading) = line.strip_prefix("## ") {
+            lines.push(paint(heading, color, |s| s.bright_green().bold()));
+        } else if let Some(item) = line.strip_prefix("- ") {
+            lines.push(format!("  • {}", render_inline(item, color)));
+        } else {
+            lines.push(render_inline(line, color));
+        }
+    }
+
+    let mut out = lines.join("\n");
+    out.push('\n');
+    out
+}
+
+/// Print text through a pager if stdout is a terminal.
+/// The pager is read from `PAGER` and defaults to `less`, which exits right away if the text fits on one screen.
+pub fn print_paged(text: &str) {
+    if !io::stdout().is_terminal() {
+        print!("{text}");
+        return;
+    }
+
+    let pager = env::var("PAGER").unwrap_or_else(|_| "less -FRX".into());
+    let mut parts = pager.split_whitespace();
+
+    let child = parts.next().and_then(|program| {
+        Command::new(program)
+            .args(parts)
+            .stdin(Stdio::piped())
+            .spawn()
+            .ok()
+    });
+
+    let Some(mut child) = child else {
+        print!("{text}");
+        return;
+    };
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // the pager may be closed before all text was written.
+        let _ = stdin.write_all(text.as_bytes());
+    }
+
+    let _ = child.wait();
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::render;
+
+    #[test]
+    fn renders_plain_text() {
+        let markdown = [
+            "## --- Day 1: Historian Hysteria ---",
+            "",
+            "The *Chief `Historian`* is `*always*` present, see [about](/2024/about).",
+            "",
+            "```",
+            "3   4",
+            "```",
+            "",
+            "- The first pair is `3 < 4` * 2.",
+        ]
+        .join("\n");
+
+        let expected = [
+            "--- Day 1: Historian Hysteria ---",
+            "",
+            "The Chief Historian is always present, see about.",
+            "",
+            "    3   4",
+            "",
+            "  • The first pair is 3 < 4 * 2.",
+            "",
+        ]
+        .join("\n");
+
+        assert_eq!(render(&markdown, false), expected);
+    }
+
+    #[test]
+    fn renders_brackets_before_links() {
+        assert_eq!(
+            render("Add [1,2] then [see](/x).", false),
+            "Add [1,2] then see.\n"
+        );
+    }
+}
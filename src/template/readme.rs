@@ -0,0 +1,334 @@
+/// Module that updates generated sections of the readme, e.g. the benchmark table.
+/// Every section is enclosed by a pair of markers that contain its name, e.g. `<!--- benchmarking table --->`.
+/// Markers only count if they are on a line of their own, so they can be quoted in the text of the readme.
+use std::{fmt::Display, fs, io, ops::Range};
+
+const README_PATH: &str = "README.md";
+
+/// A generated block of the readme.
+pub trait Section {
+    /// The name in the markers that enclose the section.
+    fn name(&self) -> &str;
+
+    /// Render the contents of the section, without its markers.
+    fn render(&self) -> String;
+
+    /// Optional sections are skipped if the readme does not contain their markers.
+    fn is_optional(&self) -> bool {
+        false
+    }
+
+    /// Whether a single marker may stand for an empty section, e.g. in readmes made for the `advent-readme-stars` action.
+    /// The marker is expanded to a pair when the section is rendered.
+    fn allows_lone_marker(&self) -> bool {
+        false
+    }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    /// Markers that are not paired correctly, with the line numbers they were found on.
+    Markers(Vec<(usize, String)>),
+    MissingSection(String),
+    IO(io::Error),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Markers(errors) => {
+                let errors: Vec<String> = errors
+                    .iter()
+                    .map(|(line, message)| format!("line {line}: {message}"))
+                    .collect();
+                write!(f, "invalid readme markers:\n{}", errors.join("\n"))
+            }
+            Error::MissingSection(name) => write!(
+                f,
+                "could not find the `{}` markers in the readme",
+                marker(name)
+            ),
+            Error::IO(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::IO(e)
+    }
+}
+
+/// The marker that opens and closes a section, e.g. `<!--- benchmarking table --->`.
+pub fn marker(name: &str) -> String {
+    format!("<!--- {name} --->")
+}
+
+/// A marker and the position of its text in the readme.
+struct Marker<'a> {
+    name: &'a str,
+    line: usize,
+    range: Range<usize>,
+}
+
+/// Find the markers on a line. Lines that contain anything besides markers do not count.
+fn parse_line<'a>(line: &'a str, offset: usize, number: usize) -> Vec<Marker<'a>> {
+    let mut markers = vec![];
+    let mut rest = line.trim_end();
+    let mut pos = offset;
+
+    loop {
+        let trimmed = rest.trim_start();
+        pos += rest.len() - trimmed.len();
+        rest = trimmed;
+
+        if rest.is_empty() {
+            return markers;
+        }
+
+        let Some(inner) = rest.strip_prefix("<!---") else {
+            return vec![];
+        };
+        let Some(len) = inner.find("--->") else {
+            return vec![];
+        };
+
+        let marker_len = "<!---".len() + len + "--->".len();
+        markers.push(Marker {
+            name: inner[..len].trim(),
+            line: number,
+            range: pos..pos + marker_len,
+        });
+
+        rest = &rest[marker_len..];
+        pos += marker_len;
+    }
+}
+
+/// Locate the sections with the given names and validate that their markers are paired.
+/// Sections in `lone` may consist of a single marker instead.
+/// Returns the range of every section that was found, including its markers.
+fn locate_sections<'a>(
+    readme: &str,
+    names: &[&'a str],
+    lone: &[&str],
+) -> Result<Vec<(&'a str, Range<usize>)>, Error> {
+    let mut markers: Vec<(&str, Marker)> = vec![];
+    let mut offset = 0;
+
+    for (i, line) in readme.split_inclusive('\n').enumerate() {
+        for found in parse_line(line, offset, i + 1) {
+            if let Some(&name) = names.iter().find(|name| **name == found.name) {
+                markers.push((name, found));
+            }
+        }
+        offset += line.len();
+    }
+
+    // a section in `lone` is marked by a single marker if its name appears only once.
+    let single: Vec<&str> = lone
+        .iter()
+        .copied()
+        .filter(|name| markers.iter().filter(|(n, _)| n == name).count() == 1)
+        .collect();
+
+    let mut sections: Vec<(&str, Range<usize>)> = vec![];
+    let mut errors: Vec<(usize, String)> = vec![];
+    let mut open: Option<Marker> = None;
+
+    for (name, found) in markers {
+        match open.take() {
+            None if single.contains(&name) => sections.push((name, found.range)),
+            Some(start) if start.name == name => {
+                sections.push((name, start.range.start..found.range.end));
+            }
+            Some(start) => {
+                errors.push((
+                    found.line,
+                    format!(
+                        "`{}` is inside the `{}` section that was opened on line {}",
+                        marker(name),
+                        start.name,
+                        start.line
+                    ),
+                ));
+                open = Some(start);
+            }
+            None if sections.iter().any(|(section, _)| *section == name) => {
+                errors.push((
+                    found.line,
+                    format!("`{}` appears more than twice", marker(name)),
+                ));
+            }
+            None => open = Some(found),
+        }
+    }
+
+    if let Some(start) = open {
+        errors.push((
+            start.line,
+            format!(
+                "`{}` is not closed, add a second marker to end the section",
+                marker(start.name)
+            ),
+        ));
+    }
+
+    if errors.is_empty() {
+        Ok(sections)
+    } else {
+        Err(Error::Markers(errors))
+    }
+}
+
+/// Render the given sections into the readme contents.
+/// Nothing is changed if one of the sections can not be updated.
+pub fn update_content(readme: &mut String, sections: &[&dyn Section]) -> Result<(), Error> {
+    let names: Vec<&str> = sections.iter().map(|section| section.name()).collect();
+    let lone: Vec<&str> = sections
+        .iter()
+        .filter(|section| section.allows_lone_marker())
+        .map(|section| section.name())
+        .collect();
+    let mut located = locate_sections(readme, &names, &lone)?;
+
+    if let Some(missing) = sections.iter().find(|section| {
+        !section.is_optional() && !located.iter().any(|(name, _)| *name == section.name())
+    }) {
+        return Err(Error::MissingSection(missing.name().into()));
+    }
+
+    // replace from the end, so that the ranges of the remaining sections stay valid.
+    located.sort_by_key(|(_, range)| std::cmp::Reverse(range.start));
+
+    for (name, range) in located {
+        let Some(section) = sections.iter().find(|section| section.name() == name) else {
+            continue;
+        };
+        let marker = marker(name);
+        readme.replace_range(range, &format!("{marker}\n{}\n{marker}", section.render()));
+    }
+
+    Ok(())
+}
+
+/// Render the given sections into `README.md`.
+pub fn update(sections: &[&dyn Section]) -> Result<(), Error> {
+    let mut readme = String::from_utf8_lossy(&fs::read(README_PATH)?).to_string();
+    update_content(&mut readme, sections)?;
+    fs::write(README_PATH, &readme)?;
+    Ok(())
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{update_content, Error, Section};
+
+    struct Text(&'static str, &'static str, bool);
+
+    impl Section for Text {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        fn render(&self) -> String {
+            self.1.into()
+        }
+
+        fn is_optional(&self) -> bool {
+            self.2
+        }
+    }
+
+    /// A section that may be marked by a single marker.
+    struct Lone(&'static str, &'static str);
+
+    impl Section for Lone {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        fn render(&self) -> String {
+            self.1.into()
+        }
+
+        fn allows_lone_marker(&self) -> bool {
+            true
+        }
+    }
+
+    fn line_numbers(result: Result<(), Error>) -> Vec<usize> {
+        match result {
+            Err(Error::Markers(errors)) => errors.into_iter().map(|(line, _)| line).collect(),
+            _ => panic!("expected marker errors"),
+        }
+    }
+
+    #[test]
+    fn updates_multiple_sections() {
+        let mut s = "# readme\n<!--- a --->\nold\n<!--- a --->\n\n<!--- b ---><!--- b --->\nfoo\n"
+            .to_string();
+        update_content(&mut s, &[&Text("a", "A", false), &Text("b", "B\nB", false)]).unwrap();
+        assert_eq!(
+            s,
+            "# readme\n<!--- a --->\nA\n<!--- a --->\n\n<!--- b --->\nB\nB\n<!--- b --->\nfoo\n"
+        );
+    }
+
+    #[test]
+    fn ignores_quoted_markers() {
+        let mut s = "<!--- a --->\n<!--- a --->\nFill the `<!--- a --->` section with `cargo a`.\n"
+            .to_string();
+        update_content(&mut s, &[&Text("a", "A", false)]).unwrap();
+        assert_eq!(
+            s,
+            "<!--- a --->\nA\n<!--- a --->\nFill the `<!--- a --->` section with `cargo a`.\n"
+        );
+    }
+
+    #[test]
+    fn skips_missing_optional_sections() {
+        let mut s = "<!--- a --->\n<!--- a --->".to_string();
+        let result = update_content(&mut s, &[&Text("a", "A", false), &Text("b", "B", true)]);
+        assert_eq!(result.is_ok(), true);
+
+        let result = update_content(&mut s, &[&Text("b", "B", false)]);
+        assert_eq!(matches!(result, Err(Error::MissingSection(_))), true);
+    }
+
+    #[test]
+    fn reports_unpaired_markers() {
+        let mut s = "foo\n<!--- a --->\nbar\n".to_string();
+        let result = update_content(&mut s, &[&Text("a", "A", false)]);
+        assert_eq!(line_numbers(result), vec![2]);
+
+        let mut s = "<!--- a --->\n<!--- a --->\n<!--- a --->\n".to_string();
+        let result = update_content(&mut s, &[&Text("a", "A", false)]);
+        assert_eq!(line_numbers(result), vec![3]);
+    }
+
+    #[test]
+    fn expands_lone_markers() {
+        let mut s = "foo\n<!--- a --->\nbar\n".to_string();
+        update_content(&mut s, &[&Lone("a", "A")]).unwrap();
+        assert_eq!(s, "foo\n<!--- a --->\nA\n<!--- a --->\nbar\n");
+
+        // once expanded, the pair is updated like any other section.
+        update_content(&mut s, &[&Lone("a", "B")]).unwrap();
+        assert_eq!(s, "foo\n<!--- a --->\nB\n<!--- a --->\nbar\n");
+
+        let mut s = "<!--- b --->\n<!--- a --->\n<!--- b --->\n".to_string();
+        let result = update_content(&mut s, &[&Lone("a", "A"), &Text("b", "B", false)]);
+        assert_eq!(line_numbers(result), vec![2]);
+    }
+
+    #[test]
+    fn reports_nested_markers() {
+        let mut s = "<!--- a --->\n<!--- b --->\n<!--- a --->\n<!--- b --->\n".to_string();
+        let result = update_content(&mut s, &[&Text("a", "A", false), &Text("b", "B", false)]);
+        assert_eq!(line_numbers(result), vec![2, 4]);
+        assert_eq!(s.contains('A'), false);
+    }
+}
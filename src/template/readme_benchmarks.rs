@@ -1,158 +1,562 @@
 /// Module that updates the readme me with timing information.
 /// The approach taken is similar to how `aoc-readme-stars` handles this.
-use std::{fs, io};
+use std::{collections::HashMap, fs};
 
+use crate::template::aoc_client::puzzle_url;
+use crate::template::budgets::{to_duration, Overrun};
+use crate::template::chart;
+use crate::template::environment::Environment;
+use crate::template::history::History;
+use crate::template::readme::{self, Error, Section};
+use crate::template::rusage::format_bytes;
 use crate::template::timings::Timings;
-use crate::template::Day;
+use crate::template::{paths, puzzle_info, Day, Year};
 
-static MARKER: &str = "<!--- benchmarking table --->";
+/// Name of the benchmark table section. Tables of a selected year use a section of their own, see [`section_name`].
+pub const SECTION: &str = "benchmarking table";
+/// Name of the optional section that summarizes the benchmarks of every year.
+pub const SUMMARY_SECTION: &str = "year summary";
+/// Name of the optional section that links the benchmark chart.
+pub const CHART_SECTION: &str = "benchmarking chart";
 
-#[allow(dead_code)]
-#[derive(Debug)]
-pub enum Error {
-    Parser(String),
-    IO(io::Error),
+/// The name of a section for the given year, e.g. `benchmarking table 2023`,
+/// so that the benchmarks of several years can be listed in the same readme.
+#[must_use]
+pub fn section_name(section: &str, year: Option<Year>) -> String {
+    match year {
+        Some(year) => format!("{section} {year}"),
+        None => section.into(),
+    }
+}
+
+/// Details of a puzzle, read from its cached description.
+struct Puzzle {
+    title: Option<String>,
+    url: Option<String>,
+    stars: usize,
 }
 
-impl From<std::io::Error> for Error {
-    fn from(e: std::io::Error) -> Self {
-        Error::IO(e)
+impl Puzzle {
+    fn title_cell(&self) -> String {
+        let title = self
+            .title
+            .as_deref()
+            .map(|title| title.split_once(": ").map_or(title, |(_, name)| name))
+            .map(|name| name.replace('|', "\\|"));
+
+        match (title, &self.url) {
+            (Some(title), Some(url)) => format!("[{title}]({url})"),
+            (Some(title), None) => title,
+            (None, Some(url)) => format!("[Puzzle]({url})"),
+            (None, None) => "-".into(),
+        }
+    }
+
+    fn stars_cell(&self) -> String {
+        match self.stars {
+            0 => "-".into(),
+            stars => "⭐".repeat(stars),
+        }
     }
 }
 
-pub struct TablePosition {
-    pos_start: usize,
-    pos_end: usize,
+/// Read the puzzle descriptions of all timed days that were downloaded.
+fn read_puzzles(year: Option<Year>, timings: &Timings) -> HashMap<Day, Puzzle> {
+    let event_year = year.or_else(Year::from_env);
+
+    timings
+        .data
+        .iter()
+        .filter_map(|timing| {
+            let markdown = fs::read_to_string(paths::puzzle_path(year, timing.day)).ok()?;
+            let puzzle = Puzzle {
+                title: puzzle_info::title(&markdown),
+                url: event_year.map(|event_year| puzzle_url(event_year, timing.day)),
+                stars: puzzle_info::answers(&markdown).len(),
+            };
+            Some((timing.day, puzzle))
+        })
+        .collect()
 }
 
+/// The link to the solution of a day, relative to `root`, the path from the document to the repository root.
 #[must_use]
-pub fn get_path_for_bin(day: Day) -> String {
-    format!("./src/bin/{day}.rs")
+pub fn get_path_for_bin(root: &str, year: Option<Year>, day: Day) -> String {
+    format!("{root}/{}", paths::bin_path(year, day))
 }
 
-fn locate_table(readme: &str) -> Result<TablePosition, Error> {
-    let matches: Vec<_> = readme.match_indices(MARKER).collect();
+#[allow(clippy::too_many_arguments)]
+fn construct_table(
+    prefix: &str,
+    root: &str,
+    year: Option<Year>,
+    timings: &Timings,
+    puzzles: &HashMap<Day, Puzzle>,
+    total_millis: f64,
+    show_memory: bool,
+    overruns: &[Overrun],
+) -> String {
+    let header = match year {
+        Some(year) => format!("{prefix} {year} Benchmarks"),
+        None => format!("{prefix} Benchmarks"),
+    };
 
-    if matches.len() > 2 {
-        return Err(Error::Parser(
-            "{}: too many occurences of marker in README.".into(),
-        ));
+    let mut lines: Vec<String> = vec![header, String::new()];
+
+    // always show at least two parts so that the table layout stays stable for regular days.
+    let part_count = timings.part_count().max(2);
+
+    // puzzle columns are only shown once puzzle descriptions were downloaded.
+    let show_puzzles = !puzzles.is_empty();
+
+    let mut columns: Vec<String> = vec![];
+    if show_puzzles {
+        columns.extend(["Puzzle".into(), "Stars".into()]);
+    }
+    columns.extend((1..=part_count).map(|n| format!("Part {n}")));
+    if show_memory {
+        columns.push("Memory".into());
+    }
+
+    lines.push(format!("| Day | {} |", columns.join(" | ")));
+    lines.push(format!(
+        "| :---: | {}  |",
+        vec![":---:"; columns.len()].join(" | ")
+    ));
+
+    for timing in &timings.data {
+        let path = get_path_for_bin(root, year, timing.day);
+        let over_budget = overruns
+            .iter()
+            .any(|overrun| overrun.day == Some(timing.day));
+        let mut row = format!(
+            "| [Day {}]({}){} |",
+            timing.day.into_inner(),
+            path,
+            if over_budget { " ⚠️" } else { "" }
+        );
+        if show_puzzles {
+            let (title, stars) = puzzles.get(&timing.day).map_or_else(
+                || ("-".into(), "-".into()),
+                |puzzle| (puzzle.title_cell(), puzzle.stars_cell()),
+            );
+            row.push_str(&format!(" {title} | {stars} |"));
+        }
+        for part in 1..=part_count {
+            row.push_str(&format!(" `{}` |", timing.part(part).unwrap_or("-")));
+        }
+        if show_memory {
+            let memory = timing.max_rss.map_or_else(|| "-".into(), format_bytes);
+            row.push_str(&format!(" `{memory}` |"));
+        }
+        lines.push(row);
+    }
+
+    lines.push(String::new());
+    match overruns.iter().find(|overrun| overrun.day.is_none()) {
+        Some(overrun) => lines.push(format!(
+            "**Total: {total_millis:.2}ms** ⚠️ (budget: {:?})",
+            to_duration(overrun.budget)
+        )),
+        None => lines.push(format!("**Total: {total_millis:.2}ms**")),
+    }
+
+    if !overruns.is_empty() {
+        lines.push(String::new());
+        lines.push("⚠️ Over the time budget.".into());
     }
 
-    let pos_start = matches
-        .first()
-        .map(|m| m.0)
-        .ok_or_else(|| Error::Parser("Could not find table start position.".into()))?;
+    if let Some(environment) = &timings.environment {
+        lines.push(String::new());
+        lines.extend(construct_environment(environment));
+    }
 
-    let pos_end = matches
-        .last()
-        .map(|m| m.0 + m.1.len())
-        .ok_or_else(|| Error::Parser("Could not find table end position.".into()))?;
+    lines.join("\n")
+}
 
-    Ok(TablePosition { pos_start, pos_end })
+/// A standalone markdown document with the benchmark table, e.g. for `cargo time --export md`.
+/// `root` is the path from the document to the repository root, so that the links to the solutions resolve.
+pub fn construct_document(root: &str, year: Option<Year>, timings: &Timings) -> String {
+    let puzzles = read_puzzles(year, timings);
+    let show_memory = timings.data.iter().any(|timing| timing.max_rss.is_some());
+    construct_table(
+        "#",
+        root,
+        year,
+        timings,
+        &puzzles,
+        timings.total_millis(),
+        show_memory,
+        &[],
+    ) + "\n"
 }
 
-fn construct_table(prefix: &str, timings: Timings, total_millis: f64) -> String {
-    let header = format!("{prefix} Benchmarks");
+fn construct_environment(environment: &Environment) -> Vec<String> {
+    let unknown = || "unknown".to_string();
+
+    let cpu = match (&environment.cpu_model, environment.cores) {
+        (Some(model), Some(cores)) => format!("{model} ({cores} cores)"),
+        (Some(model), None) => model.clone(),
+        (None, Some(cores)) => format!("{cores} cores"),
+        (None, None) => unknown(),
+    };
 
+    let target_features = if environment.target_features.is_empty() {
+        unknown()
+    } else {
+        environment.target_features.join(", ")
+    };
+
+    vec![
+        "<details>".into(),
+        "<summary>Benchmark environment</summary>".into(),
+        String::new(),
+        format!("- CPU: {cpu}"),
+        format!(
+            "- Compiler: `{}`",
+            environment.rustc_version.clone().unwrap_or_else(unknown)
+        ),
+        format!("- Profile: `{}`", environment.profile),
+        format!("- Target features: {target_features}"),
+        format!(
+            "- Commit: `{}`",
+            environment.git_commit.clone().unwrap_or_else(unknown)
+        ),
+        format!(
+            "- Sampling: {}",
+            environment.sampling.clone().unwrap_or_else(unknown)
+        ),
+        String::new(),
+        "</details>".into(),
+    ]
+}
+
+/// Summarize the stored timings of every year, e.g. `| 2023 | 12 | 3.21ms |`.
+fn construct_year_summary(years: &[(String, Timings)]) -> String {
     let mut lines: Vec<String> = vec![
-        MARKER.into(),
-        header,
+        "## Years".into(),
         String::new(),
-        "| Day | Part 1 | Part 2 |".into(),
+        "| Year | Days | Total |".into(),
         "| :---: | :---: | :---:  |".into(),
     ];
 
-    for timing in timings.data {
-        let path = get_path_for_bin(timing.day);
+    for (year, timings) in years {
         lines.push(format!(
-            "| [Day {}]({}) | `{}` | `{}` |",
-            timing.day.into_inner(),
-            path,
-            timing.part_1.unwrap_or_else(|| "-".into()),
-            timing.part_2.unwrap_or_else(|| "-".into())
+            "| {year} | {} | `{:.2}ms` |",
+            timings.data.len(),
+            timings.total_millis()
         ));
     }
 
-    lines.push(String::new());
-    lines.push(format!("**Total: {total_millis:.2}ms**"));
-    lines.push(MARKER.into());
-
     lines.join("\n")
 }
 
-fn update_content(s: &mut String, timings: Timings, total_millis: f64) -> Result<(), Error> {
-    let positions = locate_table(s)?;
-    let table = construct_table("##", timings, total_millis);
-    s.replace_range(positions.pos_start..positions.pos_end, &table);
-    Ok(())
+/// Read the stored timings of all years.
+/// Timings in `data` directly are listed under the year configured in `AOC_YEAR`.
+fn read_year_timings() -> Vec<(String, Timings)> {
+    let mut years = vec![];
+
+    let current = Timings::read_from_file(None);
+    if !current.data.is_empty() {
+        let label = Year::from_env().map_or_else(|| "Current".into(), |year| year.to_string());
+        years.push((label, current));
+    }
+
+    let mut dirs: Vec<Year> = fs::read_dir("data")
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok()?.file_name().to_str()?.parse().ok())
+        .collect();
+    dirs.sort_unstable();
+
+    for year in dirs {
+        let timings = Timings::read_from_file(Some(year));
+        if !timings.data.is_empty() {
+            years.push((year.to_string(), timings));
+        }
+    }
+
+    years
+}
+
+/// The benchmark table of a year.
+struct BenchmarkTable<'a> {
+    name: String,
+    year: Option<Year>,
+    timings: &'a Timings,
+    puzzles: &'a HashMap<Day, Puzzle>,
+    total_millis: f64,
+    show_memory: bool,
+    overruns: &'a [Overrun],
+}
+
+impl Section for BenchmarkTable<'_> {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn render(&self) -> String {
+        construct_table(
+            "##",
+            ".",
+            self.year,
+            self.timings,
+            self.puzzles,
+            self.total_millis,
+            self.show_memory,
+            self.overruns,
+        )
+    }
+}
+
+/// The summary of all years, e.g. `| 2023 | 12 | 3.21ms |`.
+struct YearSummary<'a>(&'a [(String, Timings)]);
+
+impl Section for YearSummary<'_> {
+    fn name(&self) -> &str {
+        SUMMARY_SECTION
+    }
+
+    fn render(&self) -> String {
+        construct_year_summary(self.0)
+    }
+
+    fn is_optional(&self) -> bool {
+        true
+    }
 }
 
-pub fn update(timings: Timings) -> Result<(), Error> {
-    let path = "README.md";
-    let mut readme = String::from_utf8_lossy(&fs::read(path)?).to_string();
-    let total_millis = timings.total_millis();
-    update_content(&mut readme, timings, total_millis)?;
-    fs::write(path, &readme)?;
+/// The link to the benchmark chart, e.g. `![Benchmark chart](./.assets/benchmarks.svg)`.
+struct BenchmarkChart {
+    name: String,
+    path: String,
+}
+
+impl Section for BenchmarkChart {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn render(&self) -> String {
+        format!("![Benchmark chart](./{})", self.path)
+    }
+
+    fn is_optional(&self) -> bool {
+        true
+    }
+}
+
+/// Write the benchmark chart of a year to `.assets`.
+fn store_chart(year: Option<Year>, timings: &Timings, history: &History) -> Result<(), Error> {
+    let path = paths::chart_path(year);
+    if let Some(dir) = std::path::Path::new(&path).parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(&path, chart::render(timings, &history.data))?;
     Ok(())
 }
 
+pub fn update(
+    year: Option<Year>,
+    timings: Timings,
+    history: &History,
+    overruns: &[Overrun],
+    show_memory: bool,
+) -> Result<(), Error> {
+    let puzzles = read_puzzles(year, &timings);
+    let years = read_year_timings();
+
+    let table = BenchmarkTable {
+        name: section_name(SECTION, year),
+        year,
+        timings: &timings,
+        puzzles: &puzzles,
+        total_millis: timings.total_millis(),
+        show_memory,
+        overruns,
+    };
+
+    readme::update(&[
+        &table,
+        &YearSummary(&years),
+        &BenchmarkChart {
+            name: section_name(CHART_SECTION, year),
+            path: paths::chart_path(year),
+        },
+    ])?;
+
+    // the chart is only written once the readme markers turned out to be valid.
+    store_chart(year, &timings, history)
+}
+
 #[cfg(feature = "test_lib")]
 mod tests {
-    use super::{update_content, MARKER};
-    use crate::{day, template::timings::Timing, template::timings::Timings};
+    use super::{construct_table, section_name, BenchmarkTable, Puzzle, YearSummary, SECTION};
+    use crate::{
+        day,
+        template::budgets::Overrun,
+        template::environment::Environment,
+        template::readme::{self, Error},
+        template::timings::Timing,
+        template::timings::{PartTiming, Timings},
+        template::Day,
+        template::Year,
+    };
+    use std::collections::HashMap;
+
+    const MARKER: &str = "<!--- benchmarking table --->";
+    const SUMMARY_MARKER: &str = "<!--- year summary --->";
+
+    fn update_content(
+        s: &mut String,
+        year: Option<Year>,
+        timings: Timings,
+        puzzles: &HashMap<Day, Puzzle>,
+        total_millis: f64,
+        show_memory: bool,
+    ) -> Result<(), Error> {
+        let table = BenchmarkTable {
+            name: section_name(SECTION, year),
+            year,
+            timings: &timings,
+            puzzles,
+            total_millis,
+            show_memory,
+            overruns: &[],
+        };
+        readme::update_content(s, &[&table])
+    }
+
+    fn update_summary(s: &mut String, years: &[(String, Timings)]) -> Result<(), Error> {
+        readme::update_content(s, &[&YearSummary(years)])
+    }
 
     fn get_mock_timings() -> Timings {
         Timings {
             data: vec![
                 Timing {
                     day: day!(1),
-                    part_1: Some("10ms".into()),
-                    part_2: Some("20ms".into()),
+                    parts: vec![PartTiming::new(Some("10ms")), PartTiming::new(Some("20ms"))],
                     total_nanos: 3e+10,
+                    max_rss: None,
                 },
                 Timing {
                     day: day!(2),
-                    part_1: Some("30ms".into()),
-                    part_2: Some("40ms".into()),
+                    parts: vec![PartTiming::new(Some("30ms")), PartTiming::new(Some("40ms"))],
                     total_nanos: 7e+10,
+                    max_rss: None,
                 },
                 Timing {
                     day: day!(4),
-                    part_1: Some("40ms".into()),
-                    part_2: Some("50ms".into()),
+                    parts: vec![PartTiming::new(Some("40ms")), PartTiming::new(Some("50ms"))],
                     total_nanos: 9e+10,
+                    max_rss: None,
                 },
             ],
+            environment: None,
         }
     }
 
+    #[test]
+    fn marks_overruns() {
+        let overruns = vec![
+            Overrun {
+                day: Some(day!(2)),
+                nanos: 7e+10,
+                budget: 1e+10,
+            },
+            Overrun {
+                day: None,
+                nanos: 1.9e+11,
+                budget: 1e+11,
+            },
+        ];
+        let table = construct_table(
+            "##",
+            ".",
+            None,
+            &get_mock_timings(),
+            &HashMap::new(),
+            190_000.0,
+            false,
+            &overruns,
+        );
+
+        assert_eq!(table.contains("| [Day 1](./src/bin/01.rs) |"), true);
+        assert_eq!(table.contains("| [Day 2](./src/bin/02.rs) ⚠️ |"), true);
+        assert_eq!(
+            table.contains("**Total: 190000.00ms** ⚠️ (budget: 100s)"),
+            true
+        );
+        assert_eq!(table.contains("⚠️ Over the time budget."), true);
+    }
+
     #[test]
     #[should_panic]
     fn errors_if_marker_not_present() {
         let mut s = "# readme".to_string();
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(
+            &mut s,
+            None,
+            get_mock_timings(),
+            &HashMap::new(),
+            190.0,
+            false,
+        )
+        .unwrap();
     }
 
     #[test]
     #[should_panic]
     fn errors_if_too_many_markers_present() {
         let mut s = format!("{} {} {}", MARKER, MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(
+            &mut s,
+            None,
+            get_mock_timings(),
+            &HashMap::new(),
+            190.0,
+            false,
+        )
+        .unwrap();
     }
 
     #[test]
     fn updates_empty_benchmarks() {
         let mut s = format!("foo\nbar\n{}{}\nbaz", MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(
+            &mut s,
+            None,
+            get_mock_timings(),
+            &HashMap::new(),
+            190.0,
+            false,
+        )
+        .unwrap();
         assert_eq!(s.contains("## Benchmarks"), true);
     }
 
     #[test]
     fn updates_existing_benchmarks() {
         let mut s = format!("foo\nbar\n{}{}\nbaz", MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(
+            &mut s,
+            None,
+            get_mock_timings(),
+            &HashMap::new(),
+            190.0,
+            false,
+        )
+        .unwrap();
+        update_content(
+            &mut s,
+            None,
+            get_mock_timings(),
+            &HashMap::new(),
+            190.0,
+            false,
+        )
+        .unwrap();
         assert_eq!(s.matches(MARKER).collect::<Vec<&str>>().len(), 2);
         assert_eq!(s.matches("## Benchmarks").collect::<Vec<&str>>().len(), 1);
     }
@@ -160,7 +564,15 @@ mod tests {
     #[test]
     fn format_benchmarks() {
         let mut s = format!("foo\nbar\n{}\n{}\nbaz", MARKER, MARKER);
-        update_content(&mut s, get_mock_timings(), 190.0).unwrap();
+        update_content(
+            &mut s,
+            None,
+            get_mock_timings(),
+            &HashMap::new(),
+            190.0,
+            false,
+        )
+        .unwrap();
         let expected = [
             "foo",
             "bar",
@@ -180,4 +592,166 @@ mod tests {
         .join("\n");
         assert_eq!(s, expected);
     }
+
+    #[test]
+    fn format_environment() {
+        let mut s = format!("{}{}", MARKER, MARKER);
+        let mut timings = get_mock_timings();
+        timings.environment = Some(Environment {
+            cpu_model: Some("Apple M1".into()),
+            cores: Some(8),
+            rustc_version: Some("rustc 1.83.0".into()),
+            profile: "release".into(),
+            target_features: vec!["neon".into(), "crc".into()],
+            git_commit: None,
+            sampling: Some("1s budget, 10 to 10000 samples".into()),
+        });
+        update_content(&mut s, None, timings, &HashMap::new(), 190.0, false).unwrap();
+        let expected = [
+            "**Total: 190.00ms**",
+            "",
+            "<details>",
+            "<summary>Benchmark environment</summary>",
+            "",
+            "- CPU: Apple M1 (8 cores)",
+            "- Compiler: `rustc 1.83.0`",
+            "- Profile: `release`",
+            "- Target features: neon, crc",
+            "- Commit: `unknown`",
+            "- Sampling: 1s budget, 10 to 10000 samples",
+            "",
+            "</details>",
+            "<!--- benchmarking table --->",
+        ]
+        .join("\n");
+        assert_eq!(s.ends_with(&expected), true);
+    }
+
+    #[test]
+    fn format_memory_column() {
+        let mut s = format!("{}{}", MARKER, MARKER);
+        let mut timings = get_mock_timings();
+        timings.data[0].max_rss = Some(3 * 1024 * 1024);
+        update_content(&mut s, None, timings, &HashMap::new(), 190.0, true).unwrap();
+        assert_eq!(s.contains("| Day | Part 1 | Part 2 | Memory |"), true);
+        assert_eq!(
+            s.contains("| [Day 1](./src/bin/01.rs) | `10ms` | `20ms` | `3.0 MiB` |"),
+            true
+        );
+        assert_eq!(
+            s.contains("| [Day 2](./src/bin/02.rs) | `30ms` | `40ms` | `-` |"),
+            true
+        );
+    }
+
+    #[test]
+    fn format_additional_parts() {
+        let mut s = format!("{}{}", MARKER, MARKER);
+        let mut timings = get_mock_timings();
+        timings.data[0].parts.push(PartTiming::new(Some("5ms")));
+        update_content(&mut s, None, timings, &HashMap::new(), 190.0, false).unwrap();
+        assert_eq!(s.contains("| Day | Part 1 | Part 2 | Part 3 |"), true);
+        assert_eq!(s.contains("| :---: | :---: | :---: | :---:  |"), true);
+        assert_eq!(
+            s.contains("| [Day 1](./src/bin/01.rs) | `10ms` | `20ms` | `5ms` |"),
+            true
+        );
+        assert_eq!(
+            s.contains("| [Day 2](./src/bin/02.rs) | `30ms` | `40ms` | `-` |"),
+            true
+        );
+    }
+
+    #[test]
+    fn format_puzzle_columns() {
+        let mut s = format!("{}{}", MARKER, MARKER);
+        let puzzles = HashMap::from([
+            (
+                day!(1),
+                Puzzle {
+                    title: Some("Day 1: Historian Hysteria".into()),
+                    url: Some("https://adventofcode.com/2024/day/1".into()),
+                    stars: 2,
+                },
+            ),
+            (
+                day!(2),
+                Puzzle {
+                    title: Some("Day 2: Red-Nosed Reports".into()),
+                    url: None,
+                    stars: 0,
+                },
+            ),
+        ]);
+        update_content(&mut s, None, get_mock_timings(), &puzzles, 190.0, false).unwrap();
+        assert_eq!(
+            s.contains("| Day | Puzzle | Stars | Part 1 | Part 2 |\n| :---: | :---: | :---: | :---: | :---:  |"),
+            true
+        );
+        assert_eq!(
+            s.contains("| [Day 1](./src/bin/01.rs) | [Historian Hysteria](https://adventofcode.com/2024/day/1) | ⭐⭐ | `10ms` | `20ms` |"),
+            true
+        );
+        assert_eq!(
+            s.contains("| [Day 2](./src/bin/02.rs) | Red-Nosed Reports | - | `30ms` | `40ms` |"),
+            true
+        );
+        assert_eq!(
+            s.contains("| [Day 4](./src/bin/04.rs) | - | - | `40ms` | `50ms` |"),
+            true
+        );
+    }
+
+    #[test]
+    fn format_year_benchmarks() {
+        let year_marker = "<!--- benchmarking table 2023 --->";
+        let mut s = format!("{MARKER}\n{MARKER}\n{year_marker}\n{year_marker}");
+        update_content(
+            &mut s,
+            Year::new(2023),
+            get_mock_timings(),
+            &HashMap::new(),
+            190.0,
+            false,
+        )
+        .unwrap();
+        assert_eq!(s.contains("## 2023 Benchmarks"), true);
+        assert_eq!(
+            s.contains("| [Day 1](./src/bin/2023/01.rs) | `10ms` | `20ms` |"),
+            true
+        );
+        // the table of the current year is left untouched.
+        assert_eq!(s.starts_with(&format!("{MARKER}\n{MARKER}\n")), true);
+    }
+
+    #[test]
+    fn format_year_summary() {
+        let mut s = format!("foo\n{}{}\nbar", SUMMARY_MARKER, SUMMARY_MARKER);
+        let years = vec![
+            ("2023".to_string(), get_mock_timings()),
+            ("2024".to_string(), Timings::default()),
+        ];
+        update_summary(&mut s, &years).unwrap();
+        let expected = [
+            "foo",
+            "<!--- year summary --->",
+            "## Years",
+            "",
+            "| Year | Days | Total |",
+            "| :---: | :---: | :---:  |",
+            "| 2023 | 3 | `190000.00ms` |",
+            "| 2024 | 0 | `0.00ms` |",
+            "<!--- year summary --->",
+            "bar",
+        ]
+        .join("\n");
+        assert_eq!(s, expected);
+    }
+
+    #[test]
+    fn skips_missing_year_summary() {
+        let mut s = "# readme".to_string();
+        update_summary(&mut s, &[]).unwrap();
+        assert_eq!(s, "# readme");
+    }
 }
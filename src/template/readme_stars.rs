@@ -0,0 +1,167 @@
+/// Module that updates the readme with the ⭐️ progress of a year.
+/// Stars are counted from the answers in the cached puzzle descriptions, so this works offline.
+use std::fs;
+
+use crate::template::aoc_client::puzzle_url;
+use crate::template::readme::{self, Error, Section};
+use crate::template::{all_days_in, paths, puzzle_info, Day, Year};
+
+/// Name of the stars section, compatible with the `advent-readme-stars` action.
+pub const SECTION: &str = "advent_readme_stars table";
+
+/// The number of stars of every day that was downloaded.
+fn read_stars(year: Option<Year>) -> Vec<(Day, usize)> {
+    let mut stars: Vec<(Day, usize)> = all_days_in(year)
+        .filter_map(|day| {
+            let markdown = fs::read_to_string(paths::puzzle_path(year, day)).ok()?;
+            Some((day, puzzle_info::answers(&markdown).len()))
+        })
+        .collect();
+    credit_final_star(&mut stars, all_days_in(year).count());
+    stars
+}
+
+/// The final day only has one puzzle, its second star is awarded once all other stars are collected.
+fn credit_final_star(stars: &mut [(Day, usize)], days: usize) {
+    if stars.len() != days {
+        return;
+    }
+    if let Some(((_, last), others)) = stars.split_last_mut() {
+        if *last == 1 && others.iter().all(|(_, count)| *count >= 2) {
+            *last = 2;
+        }
+    }
+}
+
+fn construct_table(year: Option<Year>, stars: &[(Day, usize)]) -> String {
+    let header = match year {
+        Some(year) => format!("## {year} Results"),
+        None => "## Results".into(),
+    };
+
+    let mut lines: Vec<String> = vec![
+        header,
+        String::new(),
+        "| Day | Part 1 | Part 2 |".into(),
+        "| :---: | :---: | :---:  |".into(),
+    ];
+
+    for (day, count) in stars {
+        let label = match year {
+            Some(year) => format!("[Day {}]({})", day.into_inner(), puzzle_url(year, *day)),
+            None => format!("Day {}", day.into_inner()),
+        };
+        let star = |part| if *count >= part { "⭐" } else { "" };
+        lines.push(format!("| {label} | {} | {} |", star(1), star(2)));
+    }
+
+    let total: usize = stars.iter().map(|(_, count)| count).sum();
+    lines.push(String::new());
+    lines.push(format!("**Total: {total} ⭐**"));
+
+    lines.join("\n")
+}
+
+/// The stars table of a year.
+struct StarsTable<'a> {
+    year: Option<Year>,
+    stars: &'a [(Day, usize)],
+}
+
+impl Section for StarsTable<'_> {
+    fn name(&self) -> &str {
+        SECTION
+    }
+
+    fn render(&self) -> String {
+        construct_table(self.year, self.stars)
+    }
+
+    fn allows_lone_marker(&self) -> bool {
+        true
+    }
+}
+
+/// Update the stars table of a year. Returns the total number of stars.
+pub fn update(year: Option<Year>) -> Result<usize, Error> {
+    let stars = read_stars(year);
+    readme::update(&[&StarsTable {
+        year: year.or_else(Year::from_env),
+        stars: &stars,
+    }])?;
+    Ok(stars.iter().map(|(_, count)| count).sum())
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{credit_final_star, StarsTable};
+    use crate::{
+        day,
+        template::{
+            readme::{self, Error},
+            Day, Year,
+        },
+    };
+
+    const MARKER: &str = "<!--- advent_readme_stars table --->";
+
+    fn update_content(
+        s: &mut String,
+        year: Option<Year>,
+        stars: &[(Day, usize)],
+    ) -> Result<(), Error> {
+        readme::update_content(s, &[&StarsTable { year, stars }])
+    }
+
+    #[test]
+    fn formats_stars() {
+        let mut s = format!("# readme\n\n{MARKER}\n{MARKER}\n\n## Benchmarks");
+        let stars = [(day!(1), 2), (day!(2), 1), (day!(3), 0)];
+        update_content(&mut s, Year::new(2024), &stars).unwrap();
+
+        assert_eq!(
+            s,
+            format!(
+                "# readme\n\n{MARKER}\n## 2024 Results\n\n| Day | Part 1 | Part 2 |\n| :---: | :---: | :---:  |\n| [Day 1](https://adventofcode.com/2024/day/1) | ⭐ | ⭐ |\n| [Day 2](https://adventofcode.com/2024/day/2) | ⭐ |  |\n| [Day 3](https://adventofcode.com/2024/day/3) |  |  |\n\n**Total: 3 ⭐**\n{MARKER}\n\n## Benchmarks"
+            )
+        );
+    }
+
+    #[test]
+    fn credits_final_star() {
+        let mut stars = [(day!(1), 2), (day!(2), 2), (day!(3), 1)];
+        credit_final_star(&mut stars, 3);
+        assert_eq!(stars[2], (day!(3), 2));
+
+        let mut stars = [(day!(1), 2), (day!(2), 1), (day!(3), 1)];
+        credit_final_star(&mut stars, 3);
+        assert_eq!(stars[2], (day!(3), 1));
+
+        let mut stars = [(day!(1), 2), (day!(2), 1)];
+        credit_final_star(&mut stars, 3);
+        assert_eq!(stars[1], (day!(2), 1));
+    }
+
+    #[test]
+    fn replaces_single_marker() {
+        let mut s = format!("# readme\n\n{MARKER}\n\n## Benchmarks");
+        update_content(&mut s, None, &[(day!(1), 2)]).unwrap();
+
+        assert_eq!(s.matches(MARKER).count(), 2);
+        assert_eq!(s.contains("| Day 1 | ⭐ | ⭐ |"), true);
+        assert_eq!(s.ends_with(&format!("{MARKER}\n\n## Benchmarks")), true);
+    }
+
+    #[test]
+    fn updates_existing_table() {
+        let mut s = format!("{MARKER}{MARKER}");
+        update_content(&mut s, None, &[(day!(1), 1)]).unwrap();
+        update_content(&mut s, None, &[(day!(1), 2)]).unwrap();
+
+        assert_eq!(s.matches(MARKER).count(), 2);
+        assert_eq!(s.contains("| Day 1 | ⭐ | ⭐ |"), true);
+        assert_eq!(s.contains("**Total: 2 ⭐**"), true);
+    }
+}
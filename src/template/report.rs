@@ -0,0 +1,496 @@
+/// Module that renders a static HTML report of an event, see `cargo report`.
+/// Pages only use local files and inline their styles, so the report can be opened without a server.
+use std::{fs, io, path::Path};
+
+use crate::template::aoc_client::puzzle_url;
+use crate::template::budgets::{to_duration, Budgets, Overrun};
+use crate::template::chart;
+use crate::template::history::History;
+use crate::template::html::{escape, highlight_rust, markdown_to_html};
+use crate::template::rusage::format_bytes;
+use crate::template::timings::{Timing, Timings};
+use crate::template::{all_days_in, paths, puzzle_info, Day, Year};
+
+const STYLE: &str = "
+body { background: #0f0f23; color: #cccccc; font-family: 'Source Code Pro', monospace; font-size: 14px; max-width: 60em; margin: 2em auto; padding: 0 1em; }
+a { color: #009900; text-decoration: none; }
+a:hover { color: #99ff99; }
+h1, h2 { color: #ffffff; font-size: 1em; font-weight: normal; }
+h1 { color: #00cc00; text-shadow: 0 0 2px #00cc00, 0 0 5px #00cc00; font-size: 1.4em; }
+em { color: #ffffff; font-style: normal; text-shadow: 0 0 5px #ffffff; }
+code { background: #10101a; border: 1px solid #333340; padding: 0 2px; }
+pre { background: #10101a; border: 1px solid #333340; padding: 0.8em; overflow-x: auto; }
+pre code { border: 0; padding: 0; }
+table { border-collapse: collapse; margin: 1em 0; }
+th, td { border: 1px solid #333340; padding: 0.3em 0.8em; text-align: center; }
+nav { margin-bottom: 1.5em; }
+.star { color: #ffff66; }
+.over { color: #ff6666; }
+.muted { color: #666666; }
+.progress { background: #10101a; border: 1px solid #333340; height: 0.8em; margin: 0.5em 0 1.5em; }
+.progress div { background: #ffff66; height: 100%; }
+.chart { background: #ffffff; display: inline-block; }
+.keyword { color: #ff9e64; }
+.string { color: #9ece6a; }
+.number { color: #e0af68; }
+.comment { color: #6b7089; font-style: italic; }
+.macro { color: #7dcfff; }
+.attribute { color: #bb9af7; }
+.lifetime { color: #bb9af7; }
+.type { color: #2ac3de; }
+";
+
+/// Everything that is known about a day from local files.
+struct DayReport {
+    day: Day,
+    /// Link to the puzzle page, if the year is known.
+    url: Option<String>,
+    puzzle: Option<String>,
+    source: Option<String>,
+    example: Option<String>,
+    has_input: bool,
+    timing: Option<Timing>,
+    overrun: Option<Overrun>,
+}
+
+impl DayReport {
+    fn read(year: Option<Year>, day: Day, timings: &Timings, overruns: &[Overrun]) -> Self {
+        let read = |path: String| fs::read_to_string(path).ok();
+
+        DayReport {
+            day,
+            url: year
+                .or_else(Year::from_env)
+                .map(|year| puzzle_url(year, day)),
+            puzzle: read(paths::puzzle_path(year, day)),
+            source: read(paths::bin_path(year, day)),
+            example: read(paths::example_path(year, day)),
+            has_input: Path::new(&paths::input_path(year, day)).exists(),
+            timing: timings.data.iter().find(|t| t.day == day).cloned(),
+            overrun: overruns.iter().find(|o| o.day == Some(day)).cloned(),
+        }
+    }
+
+    /// Days without a solution or a puzzle description are listed, but do not get a page.
+    fn has_page(&self) -> bool {
+        self.puzzle.is_some() || self.source.is_some()
+    }
+
+    fn page_name(&self) -> String {
+        format!("{}.html", self.day)
+    }
+
+    fn title(&self) -> String {
+        self.puzzle
+            .as_deref()
+            .and_then(puzzle_info::title)
+            .unwrap_or_else(|| format!("Day {}", self.day.into_inner()))
+    }
+
+    fn stars(&self) -> usize {
+        self.puzzle
+            .as_deref()
+            .map_or(0, |md| puzzle_info::answers(md).len())
+    }
+
+    fn status(&self) -> String {
+        let flags = [
+            (self.source.is_some(), "scaffolded"),
+            (self.has_input, "input"),
+            (self.timing.is_some(), "benched"),
+        ];
+        let present: Vec<&str> = flags
+            .iter()
+            .filter(|(present, _)| *present)
+            .map(|(_, label)| *label)
+            .collect();
+        if present.is_empty() {
+            "-".into()
+        } else {
+            present.join(", ")
+        }
+    }
+}
+
+/// Split a solution into its code and its `#[cfg(test)]` module.
+fn split_tests(source: &str) -> (&str, Option<&str>) {
+    let mut offset = 0;
+    for line in source.split_inclusive('\n') {
+        if line.trim_start().starts_with("#[cfg(test)]") {
+            return (source[..offset].trim_end(), Some(&source[offset..]));
+        }
+        offset += line.len();
+    }
+    (source.trim_end(), None)
+}
+
+fn stars_html(stars: usize) -> String {
+    match stars {
+        0 => r#"<span class="muted">-</span>"#.into(),
+        stars => format!(r#"<span class="star">{}</span>"#, "*".repeat(stars)),
+    }
+}
+
+fn page(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>
+<html lang=\"en\">
+<head>
+<meta charset=\"utf-8\">
+<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">
+<title>{}</title>
+<style>{STYLE}</style>
+</head>
+<body>
+{body}
+</body>
+</html>
+",
+        escape(title)
+    )
+}
+
+fn heading(year: Option<Year>) -> String {
+    match year.or_else(Year::from_env) {
+        Some(year) => format!("Advent of Code {year}"),
+        None => "Advent of Code".into(),
+    }
+}
+
+fn render_index(
+    year: Option<Year>,
+    days: &[DayReport],
+    timings: &Timings,
+    history: &History,
+    overruns: &[Overrun],
+) -> String {
+    let stars: usize = days.iter().map(DayReport::stars).sum();
+    let max_stars = days.len() * 2;
+    let solved = days.iter().filter(|day| day.stars() > 0).count();
+    let part_count = timings.part_count().max(2);
+
+    let mut body: Vec<String> = vec![
+        format!("<h1>{}</h1>", heading(year)),
+        format!(
+            r#"<p><span class="star">{stars}</span> of {max_stars} stars, {solved} of {} days solved.</p>"#,
+            days.len()
+        ),
+        format!(
+            r#"<div class="progress"><div style="width: {:.1}%"></div></div>"#,
+            if max_stars == 0 {
+                0.0
+            } else {
+                stars as f64 * 100.0 / max_stars as f64
+            }
+        ),
+    ];
+
+    let mut header = vec!["Day".to_string(), "Puzzle".into(), "Stars".into()];
+    header.extend((1..=part_count).map(|n| format!("Part {n}")));
+    header.push("Status".into());
+
+    body.push("<table>".into());
+    body.push(format!("<tr><th>{}</th></tr>", header.join("</th><th>")));
+
+    for day in days {
+        let title = if day.has_page() {
+            format!(
+                r#"<a href="{}">{}</a>"#,
+                day.page_name(),
+                escape(&day.title())
+            )
+        } else {
+            format!(r#"<span class="muted">{}</span>"#, escape(&day.title()))
+        };
+
+        let mut cells = vec![
+            day.day.into_inner().to_string(),
+            title,
+            stars_html(day.stars()),
+        ];
+        cells.extend((1..=part_count).map(|part| {
+            day.timing
+                .as_ref()
+                .and_then(|timing| timing.part(part))
+                .map_or_else(|| r#"<span class="muted">-</span>"#.into(), escape)
+        }));
+
+        let status = escape(&day.status());
+        cells.push(match &day.overrun {
+            Some(overrun) => format!(
+                r#"{status}, <span class="over" title="{}">over budget</span>"#,
+                escape(&overrun.to_string())
+            ),
+            None => status,
+        });
+
+        body.push(format!("<tr><td>{}</td></tr>", cells.join("</td><td>")));
+    }
+    body.push("</table>".into());
+
+    if !timings.data.is_empty() {
+        let total = format!("{:.2}ms", timings.total_millis());
+        match overruns.iter().find(|overrun| overrun.day.is_none()) {
+            Some(overrun) => body.push(format!(
+                r#"<p>Total: <em>{total}</em> <span class="over">over the budget of {:?}</span></p>"#,
+                to_duration(overrun.budget)
+            )),
+            None => body.push(format!("<p>Total: <em>{total}</em></p>")),
+        }
+        body.push(format!(
+            r#"<div class="chart">{}</div>"#,
+            chart::render(timings, &history.data)
+        ));
+    }
+
+    page(&heading(year), &body.join("\n"))
+}
+
+fn render_benchmarks(day: &DayReport) -> String {
+    let Some(timing) = &day.timing else {
+        return format!(
+            r#"<p class="muted">Not benched yet, run <code>cargo time {} --store</code>.</p>"#,
+            day.day
+        );
+    };
+
+    let mut lines = vec![
+        "<table>".to_string(),
+        "<tr><th>Part</th><th>Time</th><th>Nanoseconds</th><th>Allocations</th></tr>".into(),
+    ];
+
+    for (i, part) in timing.parts.iter().enumerate() {
+        let muted = || r#"<span class="muted">-</span>"#.to_string();
+        lines.push(format!(
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            i + 1,
+            part.time.as_deref().map_or_else(muted, escape),
+            part.nanos().map_or_else(muted, |nanos| format!("{nanos}")),
+            part.allocs
+                .as_ref()
+                .map_or_else(muted, |allocs| escape(&allocs.to_string())),
+        ));
+    }
+    lines.push("</table>".into());
+
+    let mut stats = vec![format!(
+        "Total: <em>{:.2}ms</em>",
+        timing.total_nanos / 1_000_000_f64
+    )];
+    if let Some(max_rss) = timing.max_rss {
+        stats.push(format!("Memory: <em>{}</em>", format_bytes(max_rss)));
+    }
+    if let Some(overrun) = &day.overrun {
+        stats.push(format!(
+            r#"<span class="over">over the budget of {:?}</span>"#,
+            to_duration(overrun.budget)
+        ));
+    }
+    lines.push(format!("<p>{}</p>", stats.join(" · ")));
+
+    lines.join("\n")
+}
+
+fn render_day(
+    year: Option<Year>,
+    day: &DayReport,
+    prev: Option<&DayReport>,
+    next: Option<&DayReport>,
+) -> String {
+    let link = |day: Option<&DayReport>, label: &str| match day {
+        Some(day) => format!(r#"<a href="{}">{label}</a>"#, day.page_name()),
+        None => format!(r#"<span class="muted">{label}</span>"#),
+    };
+
+    let mut body: Vec<String> = vec![
+        format!(
+            r#"<nav><a href="index.html">{}</a> · {} · {}</nav>"#,
+            heading(year),
+            link(prev, "previous"),
+            link(next, "next")
+        ),
+        format!("<h1>{}</h1>", escape(&day.title())),
+        format!(
+            "<p>{} {}</p>",
+            stars_html(day.stars()),
+            escape(&day.status())
+        ),
+    ];
+
+    if let Some(url) = &day.url {
+        body.push(format!(
+            r#"<p><a href="{}">Puzzle page</a></p>"#,
+            escape(url)
+        ));
+    }
+
+    body.push("<h2>--- Benchmarks ---</h2>".into());
+    body.push(render_benchmarks(day));
+
+    body.push("<h2>--- Puzzle ---</h2>".into());
+    body.push(day.puzzle.as_deref().map_or_else(
+        || {
+            format!(
+                r#"<p class="muted">Not downloaded yet, run <code>cargo read {}</code>.</p>"#,
+                day.day
+            )
+        },
+        markdown_to_html,
+    ));
+
+    let (solution, tests) = day.source.as_deref().map_or((None, None), |source| {
+        let (solution, tests) = split_tests(source);
+        (Some(solution), tests)
+    });
+
+    body.push("<h2>--- Solution ---</h2>".into());
+    body.push(solution.map_or_else(
+        || {
+            format!(
+                r#"<p class="muted">Not scaffolded yet, run <code>cargo scaffold {}</code>.</p>"#,
+                day.day
+            )
+        },
+        |solution| format!("<pre><code>{}</code></pre>", highlight_rust(solution)),
+    ));
+
+    if tests.is_some() || day.example.is_some() {
+        body.push("<h2>--- Example tests ---</h2>".into());
+        if let Some(example) = &day.example {
+            body.push(format!(
+                "<pre><code>{}</code></pre>",
+                escape(example.trim_end())
+            ));
+        }
+        if let Some(tests) = tests {
+            body.push(format!(
+                "<pre><code>{}</code></pre>",
+                highlight_rust(tests.trim_end())
+            ));
+        }
+    }
+
+    page(&day.title(), &body.join("\n"))
+}
+
+/// Write the report of a year to a directory and return the number of day pages.
+pub fn write(year: Option<Year>, dir: &str) -> io::Result<usize> {
+    let timings = Timings::read_from_file(year);
+    let history = History::read_from_file(year);
+    let overruns = Budgets::read_from_file(year)
+        .ok()
+        .flatten()
+        .map(|budgets| budgets.check(&timings))
+        .unwrap_or_default();
+
+    let days: Vec<DayReport> = all_days_in(year)
+        .map(|day| DayReport::read(year, day, &timings, &overruns))
+        .collect();
+
+    fs::create_dir_all(dir)?;
+    fs::write(
+        Path::new(dir).join("index.html"),
+        render_index(year, &days, &timings, &history, &overruns),
+    )?;
+
+    let pages: Vec<&DayReport> = days.iter().filter(|day| day.has_page()).collect();
+    for (i, day) in pages.iter().enumerate() {
+        let prev = i.checked_sub(1).map(|i| pages[i]);
+        let next = pages.get(i + 1).copied();
+        fs::write(
+            Path::new(dir).join(day.page_name()),
+            render_day(year, day, prev, next),
+        )?;
+    }
+
+    Ok(pages.len())
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{render_day, render_index, split_tests, DayReport};
+    use crate::{
+        day,
+        template::history::History,
+        template::timings::{
+            fixtures::{mock_timing, mock_timings_of},
+            Timing,
+        },
+    };
+
+    fn get_mock_timing() -> Timing {
+        Timing {
+            max_rss: Some(2048),
+            ..mock_timing(day!(1), &[Some("74.13µs"), None])
+        }
+    }
+
+    fn get_mock_day() -> DayReport {
+        DayReport {
+            day: day!(1),
+            url: None,
+            puzzle: Some(
+                "## --- Day 1: Historian Hysteria ---\n\nYour puzzle answer was `11`.\n".into(),
+            ),
+            source: Some("fn main() {}\n\n#[cfg(test)]\nmod tests {}\n".into()),
+            example: Some("3   4\n".into()),
+            has_input: true,
+            timing: Some(get_mock_timing()),
+            overrun: None,
+        }
+    }
+
+    #[test]
+    fn splits_tests() {
+        assert_eq!(
+            split_tests("fn main() {}\n\n#[cfg(test)]\nmod tests {}\n"),
+            ("fn main() {}", Some("#[cfg(test)]\nmod tests {}\n"))
+        );
+        assert_eq!(split_tests("fn main() {}\n"), ("fn main() {}", None));
+    }
+
+    #[test]
+    fn renders_index() {
+        let timings = mock_timings_of(vec![get_mock_timing()]);
+        let html = render_index(None, &[get_mock_day()], &timings, &History::default(), &[]);
+
+        assert_eq!(html.starts_with("<!DOCTYPE html>"), true);
+        assert_eq!(
+            html.contains(r#"<a href="01.html">Day 1: Historian Hysteria</a>"#),
+            true
+        );
+        assert_eq!(
+            html.contains("1</span> of 2 stars, 1 of 1 days solved."),
+            true
+        );
+        assert_eq!(html.contains("<td>74.13µs</td>"), true);
+        assert_eq!(html.contains("<svg"), true);
+    }
+
+    #[test]
+    fn renders_day() {
+        let html = render_day(None, &get_mock_day(), None, None);
+
+        assert_eq!(
+            html.contains("<title>Day 1: Historian Hysteria</title>"),
+            true
+        );
+        assert_eq!(
+            html.contains("<h2>--- Day 1: Historian Hysteria ---</h2>"),
+            true
+        );
+        assert_eq!(html.contains("<td>74130</td>"), true);
+        assert_eq!(html.contains("Memory: <em>2.0 KiB</em>"), true);
+        assert_eq!(
+            html.contains(r#"<span class="keyword">fn</span> main() {}</code></pre>"#),
+            true
+        );
+        assert_eq!(
+            html.contains(r#"<span class="attribute">#[cfg(test)]</span>"#),
+            true
+        );
+        assert_eq!(html.contains("<pre><code>3   4</code></pre>"), true);
+    }
+}
@@ -1,19 +1,32 @@
-use std::{collections::HashSet, io};
+use std::{collections::HashSet, io, process::ExitStatus};
 
-use crate::template::{Day, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
+use crate::template::{
+    runner::BenchConfig, rusage::format_bytes, Day, Year, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET,
+};
 
 use super::{
-    all_days,
+    all_days_in,
     timings::{Timing, Timings},
 };
 
-pub fn run_multi(days_to_run: &HashSet<Day>, is_release: bool, is_timed: bool) -> Option<Timings> {
+pub fn run_multi(
+    year: Option<Year>,
+    days_to_run: &HashSet<Day>,
+    is_release: bool,
+    is_timed: bool,
+    alloc_stats: bool,
+    bench_config: &BenchConfig,
+) -> Option<Timings> {
     let mut timings: Vec<Timing> = Vec::with_capacity(days_to_run.len());
 
     let mut need_space = false;
 
+    if is_timed {
+        println!("{ANSI_ITALIC}Sampling: {bench_config}{ANSI_RESET}\n");
+    }
+
     // NOTE: use non-duplicate, sorted day values.
-    all_days()
+    all_days_in(year)
         .filter(|day| days_to_run.contains(day))
         .for_each(|day| {
             if need_space {
@@ -24,18 +37,43 @@ pub fn run_multi(days_to_run: &HashSet<Day>, is_release: bool, is_timed: bool) -
             println!("{ANSI_BOLD}Day {day}{ANSI_RESET}");
             println!("------");
 
-            let output = child_commands::run_solution(day, is_timed, is_release).unwrap();
+            let output = match child_commands::run_solution(
+                year,
+                day,
+                is_timed,
+                is_release,
+                alloc_stats,
+                bench_config,
+            ) {
+                Ok(output) => output,
+                Err(Error::BuildFailed) => {
+                    println!("Failed to build solution.");
+                    return;
+                }
+                Err(Error::SolutionFailed(status)) => {
+                    println!("Solution failed ({status}).");
+                    return;
+                }
+                Err(e) => panic!("failed to run solution: {e:?}"),
+            };
 
-            if output.is_empty() {
+            if output.lines.is_empty() {
                 println!("Not solved.");
             } else {
-                let val = child_commands::parse_exec_time(&output, day);
+                if let Some(max_rss) = output.max_rss {
+                    println!("Peak memory: {}", format_bytes(max_rss));
+                }
+                let mut val = child_commands::parse_exec_time(&output.lines, day);
+                val.max_rss = output.max_rss;
                 timings.push(val);
             }
         });
 
     if is_timed {
-        let timings = Timings { data: timings };
+        let timings = Timings {
+            data: timings,
+            environment: None,
+        };
         let total_millis = timings.total_millis();
         println!(
             "\n{ANSI_BOLD}Total (Run):{ANSI_RESET} {ANSI_ITALIC}{total_millis:.2}ms{ANSI_RESET}"
@@ -50,6 +88,8 @@ pub fn run_multi(days_to_run: &HashSet<Day>, is_release: bool, is_timed: bool) -
 #[derive(Debug)]
 pub enum Error {
     BrokenPipe,
+    BuildFailed,
+    SolutionFailed(ExitStatus),
     IO(io::Error),
 }
 
@@ -59,47 +99,141 @@ impl From<std::io::Error> for Error {
     }
 }
 
-#[must_use]
-pub fn get_path_for_bin(day: Day) -> String {
-    format!("./src/bin/{day}.rs")
-}
-
 /// All solutions live in isolated binaries.
 /// This module encapsulates interaction with these binaries, both invoking them as well as parsing the timing output.
 pub mod child_commands {
-    use super::{get_path_for_bin, Error};
-    use crate::template::Day;
+    use super::Error;
+    use crate::template::{
+        alloc_stats::AllocStats,
+        paths,
+        runner::BenchConfig,
+        rusage::wait_with_max_rss,
+        timings::{parse_nanos, PartTiming},
+        Day, Year,
+    };
     use std::{
+        collections::HashMap,
+        env,
         io::{BufRead, BufReader},
-        path::Path,
+        path::{Path, PathBuf},
         process::{Command, Stdio},
+        str::FromStr,
         thread,
     };
+    use tinyjson::JsonValue;
+
+    /// Output of a solution bin: the lines it printed and its peak memory usage in bytes.
+    #[derive(Debug, Default)]
+    pub struct SolutionOutput {
+        pub lines: Vec<String>,
+        pub max_rss: Option<u64>,
+    }
+
+    /// Name of the cargo profile that solutions are built with.
+    pub fn profile(is_release: bool) -> &'static str {
+        if is_release {
+            "release"
+        } else {
+            "dev"
+        }
+    }
+
+    /// Build the solution bin for a given day and return the path of the executable.
+    /// The bin is invoked directly rather than through `cargo run`, so that resource usage is not skewed by cargo.
+    fn build_solution(
+        year: Option<Year>,
+        day: Day,
+        is_release: bool,
+        alloc_stats: bool,
+    ) -> Result<PathBuf, Error> {
+        let bin_name = paths::bin_name(year, day);
+        let mut args = vec![
+            "build",
+            "--quiet",
+            "--bin",
+            &bin_name,
+            "--message-format=json-render-diagnostics",
+            "--profile",
+            profile(is_release),
+        ];
+
+        if alloc_stats {
+            args.extend(["--features", "alloc-stats"]);
+        }
+
+        let output = Command::new("cargo")
+            .args(&args)
+            .stderr(Stdio::inherit())
+            .output()?;
+
+        if !output.status.success() {
+            return Err(Error::BuildFailed);
+        }
+
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .find_map(|line| parse_executable(line, &bin_name))
+            .ok_or(Error::BuildFailed)
+    }
+
+    /// Extract the executable path from a cargo `compiler-artifact` message for the given bin.
+    fn parse_executable(line: &str, bin: &str) -> Option<PathBuf> {
+        let json = JsonValue::from_str(line).ok()?;
+        let message = json.get::<HashMap<String, JsonValue>>()?;
+
+        let target_name = message
+            .get("target")?
+            .get::<HashMap<String, JsonValue>>()?
+            .get("name")?
+            .get::<String>()?;
+
+        if target_name != bin {
+            return None;
+        }
+
+        message
+            .get("executable")?
+            .get::<String>()
+            .map(PathBuf::from)
+    }
 
     /// Run the solution bin for a given day
-    pub fn run_solution(day: Day, is_timed: bool, is_release: bool) -> Result<Vec<String>, Error> {
+    pub fn run_solution(
+        year: Option<Year>,
+        day: Day,
+        is_timed: bool,
+        is_release: bool,
+        alloc_stats: bool,
+        bench_config: &BenchConfig,
+    ) -> Result<SolutionOutput, Error> {
         // skip command invocation for days that have not been scaffolded yet.
-        if !Path::new(&get_path_for_bin(day)).exists() {
-            return Ok(vec![]);
+        if !Path::new(&paths::bin_path(year, day)).exists() {
+            return Ok(SolutionOutput::default());
         }
 
-        let day_padded = day.to_string();
-        let mut args = vec!["run", "--quiet", "--bin", &day_padded];
+        let executable = build_solution(year, day, is_release, alloc_stats)?;
 
-        if is_release {
-            args.push("--release");
-        }
+        let mut args = vec![];
 
         if is_timed {
-            // mirror `--time` flag to child invocations.
-            args.push("--");
-            args.push("--time");
+            // mirror `--time` flag and sampling options to child invocations.
+            args.push("--time".to_string());
+            args.extend(bench_config.to_args());
+        }
+
+        // the bin is not started through cargo, so the `[env]` of `.cargo/config.toml` is forwarded explicitly.
+        let mut command = Command::new(executable);
+        if let Some(year) = year.or_else(Year::from_env) {
+            command.env("AOC_YEAR", year.to_string());
+        }
+        if let Ok(calendar) = env::var("AOC_CALENDAR") {
+            command.env("AOC_CALENDAR", calendar);
         }
 
         // spawn child command with piped stdout/stderr.
         // forward output to stdout/stderr while grabbing stdout lines.
 
-        let mut cmd = Command::new("cargo")
+        let mut cmd = command
             .args(&args)
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
@@ -123,53 +257,63 @@ pub mod child_commands {
         }
 
         thread.join().unwrap();
-        cmd.wait()?;
+        let (status, max_rss) = wait_with_max_rss(&mut cmd)?;
+
+        if !status.success() {
+            return Err(Error::SolutionFailed(status));
+        }
 
-        Ok(output)
+        Ok(SolutionOutput {
+            lines: output,
+            max_rss,
+        })
     }
 
     pub fn parse_exec_time(output: &[String], day: Day) -> super::Timing {
         let mut timings = super::Timing {
             day,
-            part_1: None,
-            part_2: None,
+            parts: vec![],
             total_nanos: 0_f64,
+            max_rss: None,
         };
 
-        output
-            .iter()
-            .filter_map(|l| {
-                if !l.contains(" samples)") {
-                    return None;
-                }
+        for line in output {
+            let Some(part) = parse_part_number(line) else {
+                continue;
+            };
 
-                let Some((timing_str, nanos)) = parse_time(l) else {
-                    eprintln!("Could not parse timings from line: {l}");
-                    return None;
-                };
-
-                let part = l.split(':').next()?;
-                Some((part, timing_str, nanos))
-            })
-            .for_each(|(part, timing_str, nanos)| {
-                if part.contains("Part 1") {
-                    timings.part_1 = Some(timing_str.into());
-                } else if part.contains("Part 2") {
-                    timings.part_2 = Some(timing_str.into());
-                }
+            if timings.parts.len() < part {
+                timings.parts.resize(part, PartTiming::default());
+            }
 
-                timings.total_nanos += nanos;
-            });
+            if !line.contains(" samples)") {
+                continue;
+            }
+
+            let Some((timing_str, nanos)) = parse_time(line) else {
+                eprintln!("Could not parse timings from line: {line}");
+                continue;
+            };
+
+            timings.parts[part - 1] = PartTiming {
+                time: Some(timing_str.into()),
+                allocs: AllocStats::parse(line),
+            };
+            timings.total_nanos += nanos;
+        }
 
         timings
     }
 
-    fn parse_to_float(s: &str, postfix: &str) -> Option<f64> {
-        s.split(postfix).next()?.parse().ok()
+    /// Extract `N` from a `Part N: ...` result line.
+    fn parse_part_number(line: &str) -> Option<usize> {
+        // intermediate results are overwritten with `\r` before the final line is printed.
+        let line = line.rsplit('\r').next()?;
+        let (part, _) = line.strip_prefix("Part ")?.split_once(':')?;
+        part.parse().ok().filter(|&n| n > 0)
     }
 
     fn parse_time(line: &str) -> Option<(&str, f64)> {
-        // for possible time formats, see: https://github.com/rust-lang/rust/blob/1.64.0/library/core/src/time.rs#L1176-L1200
         let str_timing = line
             .split(" samples)")
             .next()?
@@ -179,14 +323,7 @@ pub mod child_commands {
             .next()?
             .trim();
 
-        let parsed_timing = match str_timing {
-            s if s.contains("ns") => s.split("ns").next()?.parse::<f64>().ok(),
-            s if s.contains("µs") => parse_to_float(s, "µs").map(|x| x * 1000_f64),
-            s if s.contains("ms") => parse_to_float(s, "ms").map(|x| x * 1_000_000_f64),
-            s => parse_to_float(s, "s").map(|x| x * 1_000_000_000_f64),
-        }?;
-
-        Some((str_timing, parsed_timing))
+        Some((str_timing, parse_nanos(str_timing)?))
     }
 
     /// copied from: https://github.com/rust-lang/rust/blob/1.64.0/library/std/src/macros.rs#L328-L333
@@ -205,7 +342,8 @@ pub mod child_commands {
 
     #[cfg(feature = "test_lib")]
     mod tests {
-        use super::parse_exec_time;
+        use super::{parse_exec_time, parse_executable};
+        use std::path::PathBuf;
 
         use crate::day;
 
@@ -220,8 +358,8 @@ pub mod child_commands {
                 day!(1),
             );
             assert_approx_eq!(res.total_nanos, 74130074.13_f64);
-            assert_eq!(res.part_1.unwrap(), "74.13ns");
-            assert_eq!(res.part_2.unwrap(), "74.13ms");
+            assert_eq!(res.part(1), Some("74.13ns"));
+            assert_eq!(res.part(2), Some("74.13ms"));
         }
 
         #[test]
@@ -235,8 +373,25 @@ pub mod child_commands {
                 day!(1),
             );
             assert_approx_eq!(res.total_nanos, 2100000000_f64);
-            assert_eq!(res.part_1.unwrap(), "2s");
-            assert_eq!(res.part_2.unwrap(), "100ms");
+            assert_eq!(res.part(1), Some("2s"));
+            assert_eq!(res.part(2), Some("100ms"));
+        }
+
+        #[test]
+        fn parses_alloc_stats() {
+            let res = parse_exec_time(
+                &[
+                    "Part 1: 0 (74.13ns @ 100000 samples) [alloc: 3 allocs, 96 B total, 64 B peak]"
+                        .into(),
+                    "Part 2: 10 (74.13ms @ 99999 samples)".into(),
+                ],
+                day!(1),
+            );
+            let allocs = res.parts[0].allocs.unwrap();
+            assert_eq!(allocs.allocations, 3);
+            assert_eq!(allocs.bytes, 96);
+            assert_eq!(allocs.peak_bytes, 64);
+            assert_eq!(res.parts[1].allocs.is_none(), true);
         }
 
         #[test]
@@ -250,8 +405,41 @@ pub mod child_commands {
                 day!(1),
             );
             assert_approx_eq!(res.total_nanos, 0_f64);
-            assert_eq!(res.part_1.is_none(), true);
-            assert_eq!(res.part_2.is_none(), true);
+            assert_eq!(res.parts.len(), 2);
+            assert_eq!(res.part(1).is_none(), true);
+            assert_eq!(res.part(2).is_none(), true);
+        }
+
+        #[test]
+        fn parses_additional_parts() {
+            let res = parse_exec_time(
+                &[
+                    "Part 1: 0 (1ms @ 10 samples)".into(),
+                    "Part 2: ✖        ".into(),
+                    "Part 3: 1\rPart 3: 1 (2ms @ 10 samples)".into(),
+                    "".into(),
+                ],
+                day!(1),
+            );
+            assert_approx_eq!(res.total_nanos, 3000000_f64);
+            assert_eq!(res.parts.len(), 3);
+            assert_eq!(res.part(2), None);
+            assert_eq!(res.part(3), Some("2ms"));
+        }
+
+        #[test]
+        fn parses_executable_from_cargo_messages() {
+            let artifact = r#"{"reason":"compiler-artifact","target":{"name":"01","kind":["bin"]},"executable":"/repo/target/release/01","fresh":true}"#;
+            let lib = r#"{"reason":"compiler-artifact","target":{"name":"advent_of_code","kind":["lib"]},"executable":null,"fresh":true}"#;
+            let finished = r#"{"reason":"build-finished","success":true}"#;
+
+            assert_eq!(
+                parse_executable(artifact, "01"),
+                Some(PathBuf::from("/repo/target/release/01"))
+            );
+            assert_eq!(parse_executable(artifact, "02"), None);
+            assert_eq!(parse_executable(lib, "advent_of_code"), None);
+            assert_eq!(parse_executable(finished, "01"), None);
         }
     }
 }
@@ -2,67 +2,189 @@
 use std::fmt::Display;
 use std::hint::black_box;
 use std::io::{stdout, Write};
-use std::process::Output;
 use std::time::{Duration, Instant};
-use std::{cmp, env, process};
+use std::{cmp, env, fs, process};
 
+use crate::template::alloc_stats::AllocStats;
+use crate::template::backend::{get_backend, PuzzleBackend, Verdict};
+use crate::template::rusage::{process_cpu_time, CpuTime};
 use crate::template::ANSI_BOLD;
-use crate::template::{aoc_cli, Day, ANSI_ITALIC, ANSI_RESET};
+use crate::template::{paths, puzzle_html, Year};
+use crate::template::{Day, ANSI_ITALIC, ANSI_RESET};
 
-pub fn run_part<I: Clone, T: Display>(func: impl Fn(I) -> Option<T>, input: I, day: Day, part: u8) {
+/// Configure the size of rayon's global thread pool if `--threads <n>` was passed.
+pub fn configure_thread_pool() {
+    let args: Vec<String> = env::args().collect();
+
+    let threads = match BenchConfig::from_args(&args) {
+        Ok(config) => config.threads,
+        Err(e) => {
+            eprintln!("Invalid benchmark options: {e}");
+            process::exit(1);
+        }
+    };
+
+    if let Some(threads) = threads {
+        if let Err(e) = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build_global()
+        {
+            eprintln!("Failed to configure thread pool: {e}");
+        }
+    }
+}
+
+/// Exit if the day of a solution is not part of the calendar of its year, e.g. day 13 of an event with 12 days.
+/// Unlike the check of `day!`, this respects the `AOC_CALENDAR` override.
+pub fn check_calendar(year: Option<Year>, day: Day) {
+    let Some(year) = year.or_else(Year::from_env) else {
+        return;
+    };
+
+    if Day::new_in(day.into_inner(), year).is_none() {
+        eprintln!(
+            "Day {day} is not part of the calendar of {year}, which has {} days.",
+            year.days()
+        );
+        process::exit(1);
+    }
+}
+
+pub fn run_part<I: Clone, T: Display>(
+    func: impl Fn(I) -> Option<T>,
+    input: I,
+    year: Option<Year>,
+    day: Day,
+    part: u8,
+) {
     let part_str = format!("Part {part}");
 
-    let (result, duration, samples) =
+    let (result, measurement) =
         run_timed(func, input, |result| print_result(result, &part_str, ""));
 
-    print_result(&result, &part_str, &format_duration(&duration, samples));
+    print_result(&result, &part_str, &measurement.to_string());
 
     if let Some(result) = result {
-        submit_result(result, day, part);
+        submit_result(result, year, day, part);
     }
 }
 
 /// Run a solution part. The behavior differs depending on whether we are running a release or debug build:
 ///  1. in debug, the function is executed once.
-///  2. in release, the function is benched (approx. 1 second of execution time or 10 samples, whatever take longer.)
-fn run_timed<I: Clone, T>(
-    func: impl Fn(I) -> T,
-    input: I,
-    hook: impl Fn(&T),
-) -> (T, Duration, u128) {
+///  2. when `--time` is passed, the function is benched according to the [`BenchConfig`] read from the arguments
+///     (by default approx. 1 second of execution time or 10 samples, whatever take longer.)
+fn run_timed<I: Clone, T>(func: impl Fn(I) -> T, input: I, hook: impl Fn(&T)) -> (T, Measurement) {
+    let cpu_start = process_cpu_time();
     let timer = Instant::now();
-    let result = {
+    let (result, allocs) = {
         let input = input.clone();
 
         #[cfg(feature = "dhat-heap")]
         let _profiler = dhat::Profiler::new_heap();
 
-        func(input)
+        measure_allocs(|| func(input))
     };
     let base_time = timer.elapsed();
+    let base_cpu = cpu_elapsed(cpu_start);
 
     hook(&result);
 
-    let run = if std::env::args().any(|x| x == "--time") {
-        bench(func, input, &base_time)
+    let args: Vec<String> = env::args().collect();
+
+    let run = if args.iter().any(|x| x == "--time") {
+        let config = BenchConfig::from_args(&args).unwrap_or_else(|e| {
+            eprintln!("Invalid benchmark options: {e}");
+            process::exit(1);
+        });
+        Measurement {
+            allocs,
+            ..bench(func, input, &base_time, &config)
+        }
     } else {
-        (base_time, 1)
+        Measurement {
+            wall: base_time,
+            cpu: base_cpu,
+            samples: 1,
+            allocs,
+        }
     };
 
-    (result, run.0, run.1)
+    (result, run)
+}
+
+#[cfg(feature = "alloc-stats")]
+fn measure_allocs<T>(func: impl FnOnce() -> T) -> (T, Option<AllocStats>) {
+    let (result, stats) = crate::template::alloc_stats::measure(func);
+    (result, Some(stats))
+}
+
+#[cfg(not(feature = "alloc-stats"))]
+fn measure_allocs<T>(func: impl FnOnce() -> T) -> (T, Option<AllocStats>) {
+    (func(), None)
+}
+
+/// The average wall and CPU time per sample of a solution part.
+/// Allocations are counted for the first run only.
+struct Measurement {
+    wall: Duration,
+    cpu: Option<CpuTime>,
+    samples: u128,
+    allocs: Option<AllocStats>,
 }
 
-fn bench<I: Clone, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) -> (Duration, u128) {
+impl Display for Measurement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let wall = self.wall;
+        if self.samples == 1 {
+            write!(f, " ({wall:.1?})")?;
+        } else {
+            write!(f, " ({wall:.1?} @ {} samples)", self.samples)?;
+        }
+
+        if let Some(cpu) = self.cpu {
+            write!(
+                f,
+                " [cpu {:.1?} = {:.1?} user + {:.1?} sys",
+                cpu.total(),
+                cpu.user,
+                cpu.system
+            )?;
+            if let Some(utilization) = cpu.utilization(&wall) {
+                write!(f, ", {:.0}% utilization", utilization * 100.0)?;
+            }
+            write!(f, "]")?;
+        }
+
+        if let Some(allocs) = self.allocs {
+            write!(f, " {allocs}")?;
+        }
+
+        Ok(())
+    }
+}
+
+fn cpu_elapsed(start: Option<CpuTime>) -> Option<CpuTime> {
+    Some(process_cpu_time()?.since(&start?))
+}
+
+fn bench<I: Clone, T>(
+    func: impl Fn(I) -> T,
+    input: I,
+    base_time: &Duration,
+    config: &BenchConfig,
+) -> Measurement {
     let mut stdout = stdout();
 
     print!(" > {ANSI_ITALIC}benching{ANSI_RESET}");
     let _ = stdout.flush();
 
-    let bench_iterations =
-        (Duration::from_secs(1).as_nanos() / cmp::max(base_time.as_nanos(), 10)).clamp(10, 10000);
+    let bench_iterations = config.iterations(base_time);
 
     let mut timers: Vec<Duration> = vec![];
 
+    // `getrusage` only has microsecond resolution, so CPU time is read around the whole loop rather than per sample.
+    let cpu_start = process_cpu_time();
+
     for _ in 0..bench_iterations {
         // need a clone here to make the borrow checker happy.
         let cloned = input.clone();
@@ -71,11 +193,153 @@ fn bench<I: Clone, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) ->
         timers.push(timer.elapsed());
     }
 
-    (
+    let cpu = cpu_elapsed(cpu_start);
+
+    Measurement {
         #[allow(clippy::cast_possible_truncation)]
-        Duration::from_nanos(average_duration(&timers) as u64),
-        bench_iterations,
-    )
+        wall: Duration::from_nanos(average_duration(&timers) as u64),
+        cpu: cpu.map(|cpu| cpu.per_sample(bench_iterations)),
+        samples: bench_iterations,
+        allocs: None,
+    }
+}
+
+/// Controls how many samples are taken when a solution is benched.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BenchConfig {
+    /// A fixed number of samples. Takes precedence over `duration` and `min_samples`.
+    pub samples: Option<u128>,
+    /// The time budget that the number of samples is derived from.
+    pub duration: Duration,
+    /// The lower bound for the derived number of samples.
+    pub min_samples: u128,
+    /// The size of rayon's global thread pool. Uses rayon's default if not set.
+    pub threads: Option<usize>,
+}
+
+const MAX_SAMPLES: u128 = 10000;
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        Self {
+            samples: None,
+            duration: Duration::from_secs(1),
+            min_samples: 10,
+            threads: None,
+        }
+    }
+}
+
+impl BenchConfig {
+    /// Number of iterations to bench, based on the execution time of the first run.
+    #[must_use]
+    pub fn iterations(&self, base_time: &Duration) -> u128 {
+        self.samples.unwrap_or_else(|| {
+            (self.duration.as_nanos() / cmp::max(base_time.as_nanos(), 10))
+                .clamp(self.min_samples, cmp::max(self.min_samples, MAX_SAMPLES))
+        })
+    }
+
+    /// Arguments that mirror this config to a child invocation. Defaults are omitted.
+    #[must_use]
+    pub fn to_args(&self) -> Vec<String> {
+        let default = Self::default();
+        let mut args = vec![];
+
+        if let Some(samples) = self.samples {
+            args.extend(["--samples".into(), samples.to_string()]);
+        }
+        if self.duration != default.duration {
+            args.extend(["--duration".into(), format!("{:?}", self.duration)]);
+        }
+        if self.min_samples != default.min_samples {
+            args.extend(["--min-samples".into(), self.min_samples.to_string()]);
+        }
+        if let Some(threads) = self.threads {
+            args.extend(["--threads".into(), threads.to_string()]);
+        }
+
+        args
+    }
+
+    /// Read the config from command-line arguments, using defaults for missing options.
+    pub fn from_args(args: &[String]) -> Result<Self, String> {
+        let value_of = |flag: &str| {
+            args.iter()
+                .position(|x| x == flag)
+                .map(|i| args.get(i + 1).ok_or(format!("missing value for `{flag}`")))
+                .transpose()
+        };
+
+        let parse_count = |flag: &str| -> Result<Option<usize>, String> {
+            value_of(flag)?
+                .map(|x| match x.parse::<usize>() {
+                    Ok(n) if n > 0 => Ok(n),
+                    _ => Err(format!(
+                        "expected a positive number for `{flag}`, got `{x}`"
+                    )),
+                })
+                .transpose()
+        };
+
+        let mut config = Self {
+            samples: parse_count("--samples")?.map(|x| x as u128),
+            threads: parse_count("--threads")?,
+            ..Self::default()
+        };
+
+        if let Some(duration) = value_of("--duration")? {
+            config.duration = parse_duration(duration)?;
+        }
+
+        if let Some(min_samples) = parse_count("--min-samples")? {
+            config.min_samples = min_samples as u128;
+        }
+
+        Ok(config)
+    }
+}
+
+impl Display for BenchConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.samples {
+            Some(samples) => write!(f, "{samples} samples"),
+            None => write!(
+                f,
+                "{:?} budget, {} to {} samples",
+                self.duration,
+                self.min_samples,
+                cmp::max(self.min_samples, MAX_SAMPLES)
+            ),
+        }?;
+        match self.threads {
+            Some(threads) => write!(f, ", {threads} threads"),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Parse a duration such as `500ms`, `1.5s` or `250µs`. Plain numbers are read as seconds.
+pub fn parse_duration(s: &str) -> Result<Duration, String> {
+    let s = s.trim();
+    let (value, unit_nanos) = [
+        ("ns", 1_f64),
+        ("µs", 1e3),
+        ("us", 1e3),
+        ("ms", 1e6),
+        ("s", 1e9),
+    ]
+    .iter()
+    .find_map(|(suffix, nanos)| s.strip_suffix(suffix).map(|v| (v, *nanos)))
+    .unwrap_or((s, 1e9));
+
+    match value.trim().parse::<f64>() {
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        Ok(x) if x > 0.0 && x.is_finite() => Ok(Duration::from_nanos((x * unit_nanos) as u64)),
+        _ => Err(format!(
+            "invalid duration `{s}`, expected e.g. `500ms` or `2s`"
+        )),
+    }
 }
 
 fn average_duration(numbers: &[Duration]) -> u128 {
@@ -86,14 +350,6 @@ fn average_duration(numbers: &[Duration]) -> u128 {
         / numbers.len() as u128
 }
 
-fn format_duration(duration: &Duration, samples: u128) -> String {
-    if samples == 1 {
-        format!(" ({duration:.1?})")
-    } else {
-        format!(" ({duration:.1?} @ {samples} samples)")
-    }
-}
-
 fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str) {
     let is_intermediate_result = duration_str.is_empty();
 
@@ -130,17 +386,13 @@ fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str)
 }
 
 /// Parse the arguments passed to `solve` and try to submit one part of the solution if:
-///  1. we are in `--release` mode.
-///  2. aoc-cli is installed.
-fn submit_result<T: Display>(
-    result: T,
-    day: Day,
-    part: u8,
-) -> Option<Result<Output, aoc_cli::AocCommandError>> {
+///  1. `--submit <part>` was passed for this part.
+///  2. a puzzle backend is available.
+fn submit_result<T: Display>(result: T, year: Option<Year>, day: Day, part: u8) {
     let args: Vec<String> = env::args().collect();
 
     if !args.contains(&"--submit".into()) {
-        return None;
+        return;
     }
 
     if args.len() < 3 {
@@ -156,14 +408,117 @@ fn submit_result<T: Display>(
     };
 
     if part_submit != part {
-        return None;
+        return;
     }
 
-    if aoc_cli::check().is_err() {
-        eprintln!("command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.");
-        process::exit(1);
+    let backend = match get_backend(year) {
+        Ok(backend) => backend,
+        Err(e) => {
+            eprintln!("failed to set up backend: {e}");
+            process::exit(1);
+        }
+    };
+
+    println!("Submitting result...");
+    match backend.submit(day, part, &result.to_string()) {
+        Ok(submission) => {
+            println!("{}", submission.message);
+            if submission.verdict == Verdict::Correct {
+                refresh_puzzle(backend.as_ref(), year, day, part);
+            }
+        }
+        Err(e) => eprintln!("failed to submit result: {e}"),
     }
+}
 
-    println!("Submitting result via aoc-cli...");
-    Some(aoc_cli::submit(day, part, &result.to_string()))
+/// Update the local puzzle description once a part was solved, so that it lists the accepted answer.
+/// After part one, the newly revealed part two is printed.
+fn refresh_puzzle(backend: &dyn PuzzleBackend, year: Option<Year>, day: Day, part: u8) {
+    let path = paths::puzzle_path(year, day);
+    let previous = fs::read_to_string(&path).unwrap_or_default();
+
+    let puzzle = match backend.read(day) {
+        Ok(puzzle) => puzzle,
+        Err(e) => {
+            eprintln!("failed to refresh puzzle: {e}");
+            return;
+        }
+    };
+
+    if let Err(e) = fs::write(&path, &puzzle) {
+        eprintln!("failed to write puzzle to \"{path}\": {e}");
+        return;
+    }
+
+    let revealed = puzzle_html::revealed_lines(&previous, &puzzle);
+    if part != 1 || revealed.is_empty() {
+        return;
+    }
+
+    println!("\n🎄 Part two is unlocked, updated \"{path}\":\n");
+    for line in revealed {
+        println!("+ {line}");
+    }
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{parse_duration, BenchConfig};
+    use std::time::Duration;
+
+    fn to_args(s: &str) -> Vec<String> {
+        s.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(parse_duration("500ms"), Ok(Duration::from_millis(500)));
+        assert_eq!(parse_duration("1.5s"), Ok(Duration::from_millis(1500)));
+        assert_eq!(parse_duration("250µs"), Ok(Duration::from_micros(250)));
+        assert_eq!(parse_duration("2"), Ok(Duration::from_secs(2)));
+        assert!(parse_duration("fast").is_err());
+        assert!(parse_duration("0s").is_err());
+    }
+
+    #[test]
+    fn derives_iterations_from_base_time() {
+        let config = BenchConfig::default();
+        assert_eq!(config.iterations(&Duration::from_millis(10)), 100);
+        assert_eq!(config.iterations(&Duration::from_millis(500)), 10);
+        assert_eq!(config.iterations(&Duration::from_nanos(1)), 10000);
+
+        let config = BenchConfig {
+            duration: Duration::from_secs(10),
+            min_samples: 50,
+            ..BenchConfig::default()
+        };
+        assert_eq!(config.iterations(&Duration::from_secs(1)), 50);
+
+        let config = BenchConfig {
+            samples: Some(3),
+            ..BenchConfig::default()
+        };
+        assert_eq!(config.iterations(&Duration::from_nanos(1)), 3);
+    }
+
+    #[test]
+    fn roundtrips_args() {
+        let config = BenchConfig {
+            samples: Some(20),
+            duration: Duration::from_millis(300),
+            min_samples: 5,
+            threads: Some(1),
+        };
+        let mut args = to_args("01 --time");
+        args.extend(config.to_args());
+        assert_eq!(BenchConfig::from_args(&args), Ok(config));
+        assert_eq!(BenchConfig::default().to_args().len(), 0);
+    }
+
+    #[test]
+    fn rejects_invalid_args() {
+        assert!(BenchConfig::from_args(&to_args("--time --samples")).is_err());
+        assert!(BenchConfig::from_args(&to_args("--time --samples 0")).is_err());
+        assert!(BenchConfig::from_args(&to_args("--time --duration soon")).is_err());
+    }
 }
@@ -0,0 +1,148 @@
+/// Thin wrapper around `getrusage(2)` and `wait4(2)`, used to measure CPU time and peak memory.
+/// On platforms without `rusage`, no measurements are reported.
+use std::{
+    io,
+    process::{Child, ExitStatus},
+    time::Duration,
+};
+
+/// CPU time spent by the current process, split into user and system time.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CpuTime {
+    pub user: Duration,
+    pub system: Duration,
+}
+
+impl CpuTime {
+    pub fn total(&self) -> Duration {
+        self.user + self.system
+    }
+
+    /// CPU time spent between `earlier` and `self`.
+    #[must_use]
+    pub fn since(&self, earlier: &Self) -> Self {
+        Self {
+            user: self.user.saturating_sub(earlier.user),
+            system: self.system.saturating_sub(earlier.system),
+        }
+    }
+
+    /// Average CPU time across `n` samples.
+    #[must_use]
+    pub fn per_sample(&self, n: u128) -> Self {
+        let n = u32::try_from(n).unwrap_or(u32::MAX).max(1);
+        Self {
+            user: self.user / n,
+            system: self.system / n,
+        }
+    }
+
+    /// CPU utilization, i.e. the ratio of CPU time to wall time.
+    /// A value of `2.0` means that two cores were kept busy on average.
+    pub fn utilization(&self, wall: &Duration) -> Option<f64> {
+        if wall.is_zero() {
+            None
+        } else {
+            Some(self.total().as_secs_f64() / wall.as_secs_f64())
+        }
+    }
+}
+
+#[cfg(unix)]
+fn timeval_to_duration(tv: libc::timeval) -> Duration {
+    Duration::from_secs(u64::try_from(tv.tv_sec).unwrap_or_default())
+        + Duration::from_micros(u64::try_from(tv.tv_usec).unwrap_or_default())
+}
+
+/// CPU time used by the current process (all threads) so far.
+#[cfg(unix)]
+pub fn process_cpu_time() -> Option<CpuTime> {
+    let mut usage = std::mem::MaybeUninit::<libc::rusage>::zeroed();
+    // SAFETY: `getrusage` only writes to the provided, properly sized struct.
+    if unsafe { libc::getrusage(libc::RUSAGE_SELF, usage.as_mut_ptr()) } != 0 {
+        return None;
+    }
+    // SAFETY: the call succeeded, so the struct has been initialized.
+    let usage = unsafe { usage.assume_init() };
+    Some(CpuTime {
+        user: timeval_to_duration(usage.ru_utime),
+        system: timeval_to_duration(usage.ru_stime),
+    })
+}
+
+#[cfg(not(unix))]
+pub fn process_cpu_time() -> Option<CpuTime> {
+    None
+}
+
+/// Wait for a child process to exit, returning its peak resident set size in bytes.
+#[cfg(unix)]
+pub fn wait_with_max_rss(child: &mut Child) -> io::Result<(ExitStatus, Option<u64>)> {
+    use std::os::unix::process::ExitStatusExt;
+
+    let pid = libc::pid_t::try_from(child.id())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "invalid pid"))?;
+    let mut status = 0;
+    let mut usage = std::mem::MaybeUninit::<libc::rusage>::zeroed();
+
+    loop {
+        // SAFETY: `status` and `usage` are valid for writes for the duration of the call.
+        if unsafe { libc::wait4(pid, &mut status, 0, usage.as_mut_ptr()) } != -1 {
+            break;
+        }
+        let err = io::Error::last_os_error();
+        if err.kind() != io::ErrorKind::Interrupted {
+            return Err(err);
+        }
+    }
+
+    // SAFETY: `wait4` succeeded, so the struct has been initialized.
+    let usage = unsafe { usage.assume_init() };
+    let max_rss = u64::try_from(usage.ru_maxrss).ok().map(|rss| {
+        // linux reports kilobytes, macOS reports bytes.
+        if cfg!(target_os = "macos") {
+            rss
+        } else {
+            rss * 1024
+        }
+    });
+
+    Ok((ExitStatus::from_raw(status), max_rss))
+}
+
+#[cfg(not(unix))]
+pub fn wait_with_max_rss(child: &mut Child) -> io::Result<(ExitStatus, Option<u64>)> {
+    Ok((child.wait()?, None))
+}
+
+/// Format a byte count with binary units, e.g. `1.5 MiB`.
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    format!("{value:.1} {}", UNITS[unit])
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::format_bytes;
+
+    #[test]
+    fn formats_bytes() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(3 * 1024 * 1024), "3.0 MiB");
+        assert_eq!(format_bytes(5 * 1024 * 1024 * 1024), "5.0 GiB");
+    }
+}
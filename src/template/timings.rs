@@ -1,37 +1,85 @@
 use std::{collections::HashMap, fs, io::Error, str::FromStr};
 use tinyjson::JsonValue;
 
-use crate::template::Day;
-
-static TIMINGS_FILE_PATH: &str = "./data/timings.json";
+use crate::template::alloc_stats::AllocStats;
+use crate::template::environment::Environment;
+use crate::template::{paths, Day, Year};
+
+/// Represents the benchmark time of a single part.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PartTiming {
+    /// Formatted duration, `None` if the part is not solved.
+    pub time: Option<String>,
+    /// Allocation counts, if the solution was run with the `alloc-stats` feature.
+    pub allocs: Option<AllocStats>,
+}
 
 /// Represents benchmark times for a single day.
 #[derive(Clone, Debug)]
 pub struct Timing {
     pub day: Day,
-    pub part_1: Option<String>,
-    pub part_2: Option<String>,
+    /// Timings of each part, starting with part 1.
+    pub parts: Vec<PartTiming>,
     pub total_nanos: f64,
+    /// Peak resident set size of the solution process in bytes.
+    pub max_rss: Option<u64>,
+}
+
+impl PartTiming {
+    pub fn new(time: Option<&str>) -> Self {
+        PartTiming {
+            time: time.map(String::from),
+            allocs: None,
+        }
+    }
+
+    /// Duration in nanoseconds, `None` if the part is not solved.
+    pub fn nanos(&self) -> Option<f64> {
+        self.time.as_deref().and_then(parse_nanos)
+    }
+}
+
+fn parse_to_float(s: &str, postfix: &str) -> Option<f64> {
+    s.split(postfix).next()?.parse().ok()
+}
+
+/// Parse a formatted duration, e.g. `74.13µs`, to nanoseconds.
+pub fn parse_nanos(s: &str) -> Option<f64> {
+    // for possible time formats, see: https://github.com/rust-lang/rust/blob/1.64.0/library/core/src/time.rs#L1176-L1200
+    match s {
+        s if s.contains("ns") => s.split("ns").next()?.parse::<f64>().ok(),
+        s if s.contains("µs") => parse_to_float(s, "µs").map(|x| x * 1000_f64),
+        s if s.contains("ms") => parse_to_float(s, "ms").map(|x| x * 1_000_000_f64),
+        s => parse_to_float(s, "s").map(|x| x * 1_000_000_000_f64),
+    }
+}
+
+impl Timing {
+    /// Formatted duration of a part, starting with part 1.
+    pub fn part(&self, part: usize) -> Option<&str> {
+        self.parts.get(part.checked_sub(1)?)?.time.as_deref()
+    }
 }
 
-/// Represents benchmark times for a set of days.
+/// Represents benchmark times for a set of days, along with the environment of the most recent run.
 /// Can be serialized from / to JSON.
 #[derive(Clone, Debug, Default)]
 pub struct Timings {
     pub data: Vec<Timing>,
+    pub environment: Option<Environment>,
 }
 
 impl Timings {
-    /// Dehydrate timings to a JSON file.
-    pub fn store_file(&self) -> Result<(), Error> {
+    /// Dehydrate timings to the JSON file of a year.
+    pub fn store_file(&self, year: Option<Year>) -> Result<(), Error> {
         let json = JsonValue::from(self.clone());
-        let mut file = fs::File::create(TIMINGS_FILE_PATH)?;
+        let mut file = fs::File::create(paths::timings_path(year))?;
         json.format_to(&mut file)
     }
 
-    /// Rehydrate timings from a JSON file. If not present, returns empty timings.
-    pub fn read_from_file() -> Self {
-        fs::read_to_string(TIMINGS_FILE_PATH)
+    /// Rehydrate timings from the JSON file of a year. If not present, returns empty timings.
+    pub fn read_from_file(year: Option<Year>) -> Self {
+        fs::read_to_string(paths::timings_path(year))
             .map_err(|x| x.to_string())
             .and_then(Timings::try_from)
             .unwrap_or_default()
@@ -52,18 +100,28 @@ impl Timings {
         }
 
         data.sort_unstable_by(|a, b| a.day.cmp(&b.day));
-        Timings { data }
+        Timings {
+            data,
+            environment: new.environment.clone().or_else(|| self.environment.clone()),
+        }
     }
 
     /// Sum up total duration of timings as millis.
     pub fn total_millis(&self) -> f64 {
-        self.data.iter().map(|x| x.total_nanos).sum::<f64>() / 1_000_000_f64
+        // NOTE: an empty float sum is `-0.0`, adding zero normalizes it.
+        self.data.iter().map(|x| x.total_nanos).sum::<f64>() / 1_000_000_f64 + 0_f64
     }
 
+    /// A day is complete if every part of it has a timing.
     pub fn is_day_complete(&self, day: Day) -> bool {
-        self.data
-            .iter()
-            .any(|t| t.day == day && t.part_1.is_some() && t.part_2.is_some())
+        self.data.iter().any(|t| {
+            t.day == day && !t.parts.is_empty() && t.parts.iter().all(|p| p.time.is_some())
+        })
+    }
+
+    /// The highest number of parts of any day.
+    pub fn part_count(&self) -> usize {
+        self.data.iter().map(|t| t.parts.len()).max().unwrap_or(0)
     }
 }
 
@@ -78,6 +136,10 @@ impl From<Timings> for JsonValue {
             JsonValue::Array(value.data.iter().map(JsonValue::from).collect()),
         );
 
+        if let Some(environment) = &value.environment {
+            map.insert("environment".into(), JsonValue::from(environment));
+        }
+
         JsonValue::Object(map)
     }
 }
@@ -88,9 +150,11 @@ impl TryFrom<String> for Timings {
     fn try_from(value: String) -> Result<Self, Self::Error> {
         let json = JsonValue::from_str(&value).or(Err("not valid JSON file."))?;
 
-        let json_data = json
+        let json = json
             .get::<HashMap<String, JsonValue>>()
-            .ok_or("expected JSON document to be an object.")?
+            .ok_or("expected JSON document to be an object.")?;
+
+        let json_data = json
             .get("data")
             .ok_or("expected JSON document to have key `data`.")?
             .get::<Vec<JsonValue>>()
@@ -101,6 +165,10 @@ impl TryFrom<String> for Timings {
                 .iter()
                 .map(Timing::try_from)
                 .collect::<Result<_, _>>()?,
+            environment: json
+                .get("environment")
+                .map(Environment::try_from)
+                .transpose()?,
         })
     }
 }
@@ -114,24 +182,26 @@ impl From<&Timing> for JsonValue {
         map.insert("day".into(), JsonValue::String(value.day.to_string()));
         map.insert("total_nanos".into(), JsonValue::Number(value.total_nanos));
 
-        let part_1 = value.part_1.clone().map(JsonValue::String);
-        let part_2 = value.part_2.clone().map(JsonValue::String);
+        if let Some(max_rss) = value.max_rss {
+            #[allow(clippy::cast_precision_loss)]
+            map.insert("max_rss".into(), JsonValue::Number(max_rss as f64));
+        }
 
-        map.insert(
-            "part_1".into(),
-            match part_1 {
-                Some(x) => x,
-                None => JsonValue::Null,
-            },
-        );
+        for (i, part) in value.parts.iter().enumerate() {
+            let n = i + 1;
 
-        map.insert(
-            "part_2".into(),
-            match part_2 {
-                Some(x) => x,
-                None => JsonValue::Null,
-            },
-        );
+            map.insert(
+                format!("part_{n}"),
+                match &part.time {
+                    Some(x) => JsonValue::String(x.clone()),
+                    None => JsonValue::Null,
+                },
+            );
+
+            if let Some(allocs) = &part.allocs {
+                map.insert(format!("part_{n}_allocs"), JsonValue::from(allocs));
+            }
+        }
 
         JsonValue::Object(map)
     }
@@ -151,60 +221,118 @@ impl TryFrom<&JsonValue> for Timing {
             .and_then(|day| Day::from_str(day).ok())
             .ok_or("Expected timing.day to be a Day struct.")?;
 
-        let part_1 = json
-            .get("part_1")
-            .map(|v| if v.is_null() { None } else { v.get::<String>() })
-            .ok_or("Expected timing.part_1 to be null or string.")?;
+        if !json.contains_key("part_1") {
+            return Err("Expected timing.part_1 to be null or string.".into());
+        }
+
+        // parts are stored as `part_1`, `part_2`, ... up to the last part of a day.
+        let mut parts = vec![];
+        while let Some(v) = json.get(&format!("part_{}", parts.len() + 1)) {
+            let n = parts.len() + 1;
+
+            let time = if v.is_null() {
+                None
+            } else {
+                Some(
+                    v.get::<String>()
+                        .cloned()
+                        .ok_or(format!("Expected timing.part_{n} to be null or string."))?,
+                )
+            };
+
+            let allocs = json
+                .get(&format!("part_{n}_allocs"))
+                .map(AllocStats::try_from)
+                .transpose()?;
 
-        let part_2 = json
-            .get("part_2")
-            .map(|v| if v.is_null() { None } else { v.get::<String>() })
-            .ok_or("Expected timing.part_2 to be null or string.")?;
+            parts.push(PartTiming { time, allocs });
+        }
 
         let total_nanos = json
             .get("total_nanos")
             .and_then(|v| v.get::<f64>().copied())
             .ok_or("Expected timing.total_nanos to be a number.")?;
 
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let max_rss = json
+            .get("max_rss")
+            .and_then(|v| v.get::<f64>())
+            .map(|x| *x as u64);
+
         Ok(Timing {
             day,
-            part_1: part_1.cloned(),
-            part_2: part_2.cloned(),
+            parts,
             total_nanos,
+            max_rss,
         })
     }
 }
 
 /* -------------------------------------------------------------------------- */
 
+/// Timings for the tests of modules that present them, e.g. the chart, the exports and the report.
+#[cfg(feature = "test_lib")]
+pub mod fixtures {
+    use super::{parse_nanos, PartTiming, Timing, Timings};
+    use crate::{day, template::Day};
+
+    /// The timing of a day, its total is the sum of its solved parts.
+    pub fn mock_timing(day: Day, parts: &[Option<&str>]) -> Timing {
+        Timing {
+            day,
+            parts: parts.iter().map(|time| PartTiming::new(*time)).collect(),
+            total_nanos: parts.iter().flatten().filter_map(|t| parse_nanos(t)).sum(),
+            max_rss: None,
+        }
+    }
+
+    pub fn mock_timings_of(data: Vec<Timing>) -> Timings {
+        Timings {
+            data,
+            environment: None,
+        }
+    }
+
+    /// Two days with parts that range from microseconds to seconds, one part is not solved.
+    pub fn mock_timings() -> Timings {
+        mock_timings_of(vec![
+            mock_timing(day!(1), &[Some("74.13µs"), Some("2.5ms")]),
+            mock_timing(day!(2), &[Some("1.2s"), None]),
+        ])
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
 #[cfg(feature = "test_lib")]
 mod tests {
     use crate::day;
 
-    use super::{Timing, Timings};
+    use super::{PartTiming, Timing, Timings};
 
     fn get_mock_timings() -> Timings {
         Timings {
             data: vec![
                 Timing {
                     day: day!(1),
-                    part_1: Some("10ms".into()),
-                    part_2: Some("20ms".into()),
+                    parts: vec![PartTiming::new(Some("10ms")), PartTiming::new(Some("20ms"))],
                     total_nanos: 3e+10,
+                    max_rss: None,
                 },
                 Timing {
                     day: day!(2),
-                    part_1: Some("30ms".into()),
-                    part_2: Some("40ms".into()),
+                    parts: vec![PartTiming::new(Some("30ms")), PartTiming::new(Some("40ms"))],
                     total_nanos: 7e+10,
+                    max_rss: None,
                 },
                 Timing {
                     day: day!(4),
-                    part_1: Some("40ms".into()),
-                    part_2: None,
+                    parts: vec![PartTiming::new(Some("40ms")), PartTiming::new(None)],
                     total_nanos: 4e+10,
+                    max_rss: None,
                 },
             ],
+            environment: None,
         }
     }
 
@@ -218,11 +346,23 @@ mod tests {
             assert_eq!(timings.data.len(), 1);
             let timing = timings.data.first().unwrap();
             assert_eq!(timing.day, day!(1));
-            assert_eq!(timing.part_1, Some("1ms".to_string()));
-            assert_eq!(timing.part_2, None);
+            assert_eq!(timing.part(1), Some("1ms"));
+            assert_eq!(timing.part(2), None);
+            assert_eq!(timing.parts.len(), 2);
             assert_eq!(timing.total_nanos, 1_000_000_000_f64);
         }
 
+        #[test]
+        fn handles_more_than_two_parts() {
+            let json = r#"{ "data": [{ "day": "01", "part_1": "1ms", "part_2": "2ms", "part_3": null, "part_3_allocs": { "allocations": 1, "bytes": 8, "peak_bytes": 8 }, "total_nanos": 3000000 }] }"#.to_string();
+            let timings = Timings::try_from(json).unwrap();
+            let timing = timings.data.first().unwrap();
+            assert_eq!(timing.parts.len(), 3);
+            assert_eq!(timing.part(2), Some("2ms"));
+            assert_eq!(timing.part(3), None);
+            assert_eq!(timing.parts[2].allocs.as_ref().unwrap().allocations, 1);
+        }
+
         #[test]
         fn handles_empty_timings() {
             let json = r#"{ "data": [] }"#.to_string();
@@ -271,7 +411,7 @@ mod tests {
     mod is_day_complete {
         use crate::{
             day,
-            template::timings::{Timing, Timings},
+            template::timings::{PartTiming, Timing, Timings},
         };
 
         #[test]
@@ -279,13 +419,14 @@ mod tests {
             let timings = Timings {
                 data: vec![Timing {
                     day: day!(1),
-                    part_1: Some("1ms".into()),
-                    part_2: Some("2ms".into()),
+                    parts: vec![PartTiming::new(Some("1ms")), PartTiming::new(Some("2ms"))],
                     total_nanos: 3_000_000_000_f64,
+                    max_rss: None,
                 }],
+                environment: None,
             };
 
-            assert_eq!(timings.is_day_complete(&day!(1)), true);
+            assert_eq!(timings.is_day_complete(day!(1)), true);
         }
 
         #[test]
@@ -293,13 +434,14 @@ mod tests {
             let timings = Timings {
                 data: vec![Timing {
                     day: day!(1),
-                    part_1: Some("1ms".into()),
-                    part_2: None,
+                    parts: vec![PartTiming::new(Some("1ms")), PartTiming::new(None)],
                     total_nanos: 1_000_000_000_f64,
+                    max_rss: None,
                 }],
+                environment: None,
             };
 
-            assert_eq!(timings.is_day_complete(&day!(1)), false);
+            assert_eq!(timings.is_day_complete(day!(1)), false);
         }
 
         #[test]
@@ -307,20 +449,21 @@ mod tests {
             let timings = Timings {
                 data: vec![Timing {
                     day: day!(1),
-                    part_1: None,
-                    part_2: None,
+                    parts: vec![PartTiming::new(None), PartTiming::new(None)],
                     total_nanos: 0.0,
+                    max_rss: None,
                 }],
+                environment: None,
             };
 
-            assert_eq!(timings.is_day_complete(&day!(1)), false);
+            assert_eq!(timings.is_day_complete(day!(1)), false);
         }
     }
 
     mod merge {
         use crate::{
             day,
-            template::timings::{Timing, Timings},
+            template::timings::{PartTiming, Timing, Timings},
         };
 
         use super::get_mock_timings;
@@ -331,10 +474,11 @@ mod tests {
             let other = Timings {
                 data: vec![Timing {
                     day: day!(3),
-                    part_1: None,
-                    part_2: None,
+                    parts: vec![PartTiming::new(None), PartTiming::new(None)],
                     total_nanos: 0_f64,
+                    max_rss: None,
                 }],
+                environment: None,
             };
             let merged = timings.merge(&other);
             assert_eq!(merged.data.len(), 4);
@@ -351,10 +495,11 @@ mod tests {
             let other = Timings {
                 data: vec![Timing {
                     day: day!(2),
-                    part_1: None,
-                    part_2: None,
+                    parts: vec![PartTiming::new(None), PartTiming::new(None)],
                     total_nanos: 0_f64,
+                    max_rss: None,
                 }],
+                environment: None,
             };
             let merged = timings.merge(&other);
 
@@ -0,0 +1,167 @@
+use std::env;
+use std::error::Error;
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// The first year of advent of code.
+const FIRST_YEAR: u16 = 2015;
+
+/// The most days an event has.
+pub const MAX_DAYS: u8 = 25;
+
+/// Number of days in the built-in calendar of a year.
+/// Events up to 2024 have 25 days, later events have 12.
+pub const fn calendar_days(year: u16) -> u8 {
+    if year >= 2025 {
+        12
+    } else {
+        MAX_DAYS
+    }
+}
+
+/// Read the number of days of a year from `AOC_CALENDAR`, e.g. `AOC_CALENDAR = "2025=12,2026=12"`.
+fn calendar_override(year: u16) -> Option<u8> {
+    env::var("AOC_CALENDAR").ok()?.split(',').find_map(|entry| {
+        let (y, days) = entry.split_once('=')?;
+        if y.trim().parse::<u16>().ok()? != year {
+            return None;
+        }
+        days.trim()
+            .parse()
+            .ok()
+            .filter(|days| (1..=MAX_DAYS).contains(days))
+    })
+}
+
+/// A valid year of advent of code (i.e. 2015 or later).
+///
+/// # Display
+/// This value displays as a four digit number.
+///
+/// ```
+/// # use advent_of_code::template::Year;
+/// let year = Year::new(2023).unwrap();
+/// assert_eq!(year.to_string(), "2023")
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Year(u16);
+
+impl Year {
+    /// Creates a [`Year`] from the provided value if it's in the valid range,
+    /// returns [`None`] otherwise.
+    pub const fn new(year: u16) -> Option<Self> {
+        if year < FIRST_YEAR {
+            return None;
+        }
+        Some(Self(year))
+    }
+
+    /// Converts the [`Year`] into an [`u16`].
+    pub fn into_inner(self) -> u16 {
+        self.0
+    }
+
+    /// Returns the number of days in this year's calendar.
+    /// The built-in calendar can be overridden with `AOC_CALENDAR`.
+    pub fn days(self) -> u8 {
+        calendar_override(self.0).unwrap_or_else(|| calendar_days(self.0))
+    }
+
+    /// Reads the year configured in `AOC_YEAR`.
+    pub fn from_env() -> Option<Self> {
+        env::var("AOC_YEAR").ok()?.parse().ok()
+    }
+
+    /// Extracts the year from the path of a solution in a per-year directory, e.g. `src/bin/2023/01.rs`.
+    /// Returns [`None`] for solutions that live in `src/bin` directly.
+    pub const fn from_bin_path(path: &str) -> Option<Self> {
+        const fn is_separator(b: u8) -> bool {
+            b == b'/' || b == b'\\'
+        }
+
+        let bytes = path.as_bytes();
+
+        // look for `bin/<year>/` and parse the year.
+        let mut i = 0;
+        while i + 9 <= bytes.len() {
+            if bytes[i] == b'b'
+                && bytes[i + 1] == b'i'
+                && bytes[i + 2] == b'n'
+                && is_separator(bytes[i + 3])
+                && is_separator(bytes[i + 8])
+            {
+                let mut year = 0;
+                let mut j = i + 4;
+                while j < i + 8 && bytes[j].is_ascii_digit() {
+                    year = year * 10 + (bytes[j] - b'0') as u16;
+                    j += 1;
+                }
+                if j == i + 8 {
+                    return Self::new(year);
+                }
+            }
+            i += 1;
+        }
+
+        None
+    }
+}
+
+impl Display for Year {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for Year {
+    type Err = YearFromStrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let year = s.parse().map_err(|_| YearFromStrError)?;
+        Self::new(year).ok_or(YearFromStrError)
+    }
+}
+
+/// An error which can be returned when parsing a [`Year`].
+#[derive(Debug)]
+pub struct YearFromStrError;
+
+impl Error for YearFromStrError {}
+
+impl Display for YearFromStrError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("expecting a year of 2015 or later")
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::{calendar_days, Year};
+
+    #[test]
+    fn parses_years() {
+        assert_eq!("2023".parse::<Year>().unwrap(), Year(2023));
+        assert_eq!("2014".parse::<Year>().is_err(), true);
+        assert_eq!("twenty".parse::<Year>().is_err(), true);
+    }
+
+    #[test]
+    fn looks_up_calendar() {
+        assert_eq!(calendar_days(2015), 25);
+        assert_eq!(calendar_days(2024), 25);
+        assert_eq!(calendar_days(2025), 12);
+    }
+
+    #[test]
+    fn extracts_year_from_bin_path() {
+        assert_eq!(Year::from_bin_path("src/bin/2023/01.rs"), Some(Year(2023)));
+        assert_eq!(
+            Year::from_bin_path("C:\\aoc\\src\\bin\\2016\\25.rs"),
+            Some(Year(2016))
+        );
+        assert_eq!(Year::from_bin_path("src/bin/01.rs"), None);
+        assert_eq!(Year::from_bin_path("src/bin/20x3/01.rs"), None);
+    }
+}
@@ -0,0 +1,85 @@
+//! Runs the scaffold, download, read, solve and submit flow against the fake backend.
+#![cfg(feature = "test_lib")]
+
+use std::{
+    env, fs,
+    path::Path,
+    process::{self, Command, Output},
+};
+
+fn run(program: &str, args: &[&str], cwd: &Path, fake_dir: &Path) -> Output {
+    let output = Command::new(program)
+        .args(args)
+        .current_dir(cwd)
+        .env("AOC_BACKEND", "fake")
+        .env("AOC_FAKE_DIR", fake_dir)
+        .output()
+        .unwrap();
+
+    assert!(
+        output.status.success(),
+        "`{program} {}` failed: {}",
+        args.join(" "),
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    output
+}
+
+#[test]
+fn solves_and_submits_offline() {
+    let root = env::temp_dir().join(format!("aoc-offline-flow-{}", process::id()));
+    let repo = root.join("repo");
+    let fake = root.join("fake");
+
+    for dir in ["src/bin", "data/inputs", "data/examples", "data/puzzles"] {
+        fs::create_dir_all(repo.join(dir)).unwrap();
+    }
+    for dir in ["inputs", "puzzles", "answers"] {
+        fs::create_dir_all(fake.join(dir)).unwrap();
+    }
+
+    let example = fs::read_to_string("data/examples/01.txt").unwrap();
+    fs::write(fake.join("inputs/01.txt"), &example).unwrap();
+    fs::write(fake.join("puzzles/01.md"), "## --- Day 1: Offline ---\n").unwrap();
+    fs::write(
+        fake.join("puzzles/01-2.md"),
+        "## --- Day 1: Offline ---\n\nYour puzzle answer was `4`.\n\n## --- Part Two ---\n",
+    )
+    .unwrap();
+    fs::write(fake.join("answers/01-1.txt"), "4\n").unwrap();
+
+    let cli = env!("CARGO_BIN_EXE_advent_of_code");
+    run(cli, &["scaffold", "1"], &repo, &fake);
+    assert_eq!(repo.join("src/bin/01.rs").exists(), true);
+
+    run(cli, &["download", "1"], &repo, &fake);
+    assert_eq!(
+        fs::read_to_string(repo.join("data/inputs/01.txt")).unwrap(),
+        example
+    );
+
+    let read = run(cli, &["read", "1"], &repo, &fake);
+    assert_eq!(
+        String::from_utf8_lossy(&read.stdout).contains("Day 1: Offline"),
+        true
+    );
+
+    let solve = run(env!("CARGO_BIN_EXE_01"), &["--submit", "1"], &repo, &fake);
+    let stdout = String::from_utf8_lossy(&solve.stdout);
+    assert_eq!(stdout.contains("That's the right answer!"), true);
+    assert_eq!(stdout.contains("+ ## --- Part Two ---"), true);
+    assert_eq!(stdout.contains("+ ## --- Day 1"), false);
+    assert_eq!(
+        fs::read_to_string(repo.join("data/puzzles/01.md"))
+            .unwrap()
+            .contains("Part Two"),
+        true
+    );
+    assert_eq!(
+        fs::read_to_string(fake.join("submissions.log")).unwrap(),
+        "01-1: 4\n"
+    );
+
+    fs::remove_dir_all(root).unwrap();
+}